@@ -0,0 +1,167 @@
+use bevy::{ecs::world::Command, prelude::*, utils::HashSet};
+
+use crate::input::{actions::XrActionType, config::ConfigError, validation::XrConfigProblem};
+
+#[derive(Debug)]
+pub enum XrUtilsError {
+    Config(ConfigError),
+    /// Everything [`Config::validate`](crate::prelude::Config::validate) found wrong
+    InvalidConfig(Vec<XrConfigProblem>),
+    CreateActionSet {
+        name: String,
+        result: openxr::sys::Result,
+    },
+    CreateAction {
+        name: String,
+        result: openxr::sys::Result,
+    },
+    InvalidPath {
+        path: String,
+        result: openxr::sys::Result,
+    },
+    /// A call into the runtime failed, `what` says which
+    Runtime {
+        what: String,
+        result: openxr::sys::Result,
+    },
+    MissingAction(String),
+    WrongActionType {
+        name: String,
+        expected: XrActionType,
+    },
+    MissingRapierContext,
+    /// The action config changed after the instance locked in the bindings
+    RestartRequired,
+}
+
+impl std::fmt::Display for XrUtilsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XrUtilsError::Config(e) => write!(f, "{e}"),
+            XrUtilsError::InvalidConfig(problems) => {
+                write!(f, "invalid action config:")?;
+                for problem in problems {
+                    write!(f, "\n  {problem}")?;
+                }
+                Ok(())
+            }
+            XrUtilsError::CreateActionSet { name, result } => {
+                write!(f, "runtime refused to create action set {name}: {result}")
+            }
+            XrUtilsError::CreateAction { name, result } => {
+                write!(f, "runtime refused to create action {name}: {result}")
+            }
+            XrUtilsError::InvalidPath { path, result } => {
+                write!(f, "invalid path {path}: {result}")
+            }
+            XrUtilsError::Runtime { what, result } => write!(f, "{what}: {result}"),
+            XrUtilsError::MissingAction(name) => write!(f, "no action called {name}"),
+            XrUtilsError::WrongActionType { name, expected } => {
+                write!(f, "action {name} isn't a {expected:?} action")
+            }
+            XrUtilsError::MissingRapierContext => write!(f, "no RapierContext in the world"),
+            XrUtilsError::RestartRequired => write!(
+                f,
+                "action config changed after its bindings were suggested, restart to apply it"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for XrUtilsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XrUtilsError::Config(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConfigError> for XrUtilsError {
+    fn from(e: ConfigError) -> Self {
+        XrUtilsError::Config(e)
+    }
+}
+
+/// Parts of the crate that get switched off when they hit an error they can't recover from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum XrSubsystem {
+    Actions,
+    TrackingRig,
+    Haptics,
+    Grab,
+    GravityGrab,
+    Rebinding,
+}
+
+/// Sent for every error the crate runs into
+#[derive(Event, Debug)]
+pub struct XrUtilsErrorEvent {
+    pub subsystem: XrSubsystem,
+    pub error: XrUtilsError,
+    /// If the subsystem got disabled because of it
+    pub disabled: bool,
+}
+
+/// Subsystems that are switched off, remove one to try it again
+#[derive(Resource, Default, Debug)]
+pub struct XrDisabledSubsystems(pub HashSet<XrSubsystem>);
+
+impl XrDisabledSubsystems {
+    pub fn is_disabled(&self, subsystem: XrSubsystem) -> bool {
+        self.0.contains(&subsystem)
+    }
+}
+
+/// Run condition that skips systems of a disabled subsystem
+pub fn subsystem_enabled(
+    subsystem: XrSubsystem,
+) -> impl FnMut(Option<Res<XrDisabledSubsystems>>) -> bool + Clone {
+    move |disabled: Option<Res<XrDisabledSubsystems>>| {
+        disabled.is_none_or(|disabled| !disabled.is_disabled(subsystem))
+    }
+}
+
+/// Logs the error, sends an [`XrUtilsErrorEvent`] and optionally disables the subsystem
+pub struct ReportXrError {
+    pub subsystem: XrSubsystem,
+    pub error: XrUtilsError,
+    pub disable: bool,
+}
+
+impl ReportXrError {
+    pub fn warn(subsystem: XrSubsystem, error: XrUtilsError) -> Self {
+        Self {
+            subsystem,
+            error,
+            disable: false,
+        }
+    }
+
+    pub fn disable(subsystem: XrSubsystem, error: XrUtilsError) -> Self {
+        Self {
+            subsystem,
+            error,
+            disable: true,
+        }
+    }
+}
+
+impl Command for ReportXrError {
+    fn apply(self, world: &mut World) {
+        if self.disable {
+            error!("{}, disabling {:?}", self.error, self.subsystem);
+            world
+                .get_resource_or_insert_with(XrDisabledSubsystems::default)
+                .0
+                .insert(self.subsystem);
+        } else {
+            warn!("{}", self.error);
+        }
+        world.send_event(XrUtilsErrorEvent {
+            subsystem: self.subsystem,
+            error: self.error,
+            disabled: self.disable,
+        });
+    }
+}
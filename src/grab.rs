@@ -3,7 +3,8 @@ use bevy_mod_xr::spaces::XrVelocity;
 use bevy_rapier3d::prelude::*;
 
 use crate::{
-    prelude::{XrAction, XrInput, XrTrackedSpace},
+    error::{ReportXrError, XrSubsystem, XrUtilsError},
+    prelude::{Hand, XrHapticPulse, XrInput, XrInputContexts, XrTrackedSpace},
     XrUtilsConfig,
 };
 
@@ -12,19 +13,33 @@ use crate::{
 pub struct Grabbable;
 
 #[derive(Component)]
-/// Component to mark an entity as holding
-pub struct Holding;
+/// Marks an entity as held by the tracked space entity inside, one per hand
+pub struct Holding(pub Entity);
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn grab(
     mut commands: Commands,
     inputs: Option<Res<XrInput>>,
+    contexts: Res<XrInputContexts>,
     hand_query: Query<
-        (&GlobalTransform, &XrVelocity, Entity, &Collider),
+        (
+            &GlobalTransform,
+            &XrVelocity,
+            Entity,
+            &Collider,
+            Option<&Hand>,
+        ),
         (With<XrTrackedSpace>, Without<Holding>, Without<Grabbable>),
     >,
     mut holding_query: Query<
-        (&mut Velocity, &mut Transform, &GlobalTransform, Entity),
-        (With<Holding>, Without<XrTrackedSpace>),
+        (
+            &mut Velocity,
+            &mut Transform,
+            &GlobalTransform,
+            Entity,
+            &Holding,
+        ),
+        Without<XrTrackedSpace>,
     >,
     mut grabbable_query: Query<
         (&mut Transform, &Aabb, Entity),
@@ -37,47 +52,83 @@ pub(crate) fn grab(
     >,
     rapier_context: Query<&RapierContext>,
     config: Res<XrUtilsConfig>,
+    mut haptics: EventWriter<XrHapticPulse>,
 ) {
-    if let Some((hand_transform, velocity, hand_entity, hand_collider)) = hand_query.iter().last() {
+    let Some(inputs) = inputs else {
+        return;
+    };
+    if hand_query.is_empty() {
+        return;
+    }
+    let Ok(rapier_context) = rapier_context.get_single() else {
+        commands.queue(ReportXrError::disable(
+            XrSubsystem::Grab,
+            XrUtilsError::MissingRapierContext,
+        ));
+        return;
+    };
+    // the inserts are deferred, keeps both hands from taking the same entity this frame
+    let mut grabbed = Vec::new();
+    for (hand_transform, velocity, hand_entity, hand_collider, hand) in hand_query.iter() {
         let hand_transform = hand_transform.compute_transform();
-        let (xr_action, pose_action) = config.grab_action_names.first().unwrap();
-        if let Some(input) = inputs {
-            let input = input.state.get(xr_action).unwrap().as_float().unwrap();
-            if let Ok((mut linear_vel, mut transform, global_transform, entity)) =
-                holding_query.get_single_mut()
-            {
-                if input.cur_val <= 0.0 {
-                    *transform = global_transform.compute_transform();
-                    commands.entity(hand_entity).remove_children(&[entity]);
-                    commands.entity(entity).remove::<Holding>();
-                    commands.entity(entity).remove::<RigidBodyDisabled>();
-                    linear_vel.linvel = velocity.linear;
-                    return;
+        let input = match inputs.float_state(&config.grab_action, hand.copied()) {
+            Ok(input) => input,
+            Err(e) => {
+                commands.queue(ReportXrError::disable(XrSubsystem::Grab, e));
+                return;
+            }
+        };
+        let mut holds = false;
+        for (mut linear_vel, mut transform, global_transform, entity, holding) in
+            holding_query.iter_mut()
+        {
+            if holding.0 != hand_entity {
+                continue;
+            }
+            holds = true;
+            if !input.is_pressed {
+                *transform = global_transform.compute_transform();
+                commands.entity(hand_entity).remove_children(&[entity]);
+                commands.entity(entity).remove::<Holding>();
+                commands.entity(entity).remove::<RigidBodyDisabled>();
+                linear_vel.linvel = velocity.linear;
+                if let (Some(hand), Some(haptic)) = (hand, config.release_haptic) {
+                    haptics.send(haptic.on(*hand));
                 }
             }
+        }
+        if holds {
+            continue;
+        }
 
-            for entity in rapier_context
-                .get_single()
-                .unwrap()
-                .intersection_with_shape(
-                    hand_transform.translation,
-                    hand_transform.rotation,
-                    &hand_collider,
-                    QueryFilter::only_dynamic(),
-                )
-                .iter()
-            {
-                if input.pressed {
-                    if let Ok((mut transform, aabb, entity)) = grabbable_query.get_mut(*entity) {
-                        let translation = -Vec3::new(aabb.half_extents.x, 0.0, aabb.half_extents.z)
-                            - Vec3::new(0.025, 0.0, 0.0);
-                        // - Vec3::new(0.05, 0.0, 0.0);
-                        transform.translation = translation;
-                        transform.rotation = Quat::IDENTITY;
-                        commands.entity(entity).insert(Holding);
-                        commands.entity(entity).insert(RigidBodyDisabled);
-                        commands.entity(hand_entity).add_child(entity);
-                    }
+        // a menu or other context already used the squeeze
+        if !input.pressed || contexts.is_consumed(&config.grab_action, hand.copied()) {
+            continue;
+        }
+        for entity in rapier_context
+            .intersection_with_shape(
+                hand_transform.translation,
+                hand_transform.rotation,
+                hand_collider,
+                QueryFilter::only_dynamic(),
+            )
+            .iter()
+        {
+            if grabbed.contains(entity) {
+                continue;
+            }
+            if let Ok((mut transform, aabb, entity)) = grabbable_query.get_mut(*entity) {
+                let translation = -Vec3::new(aabb.half_extents.x, 0.0, aabb.half_extents.z)
+                    - Vec3::new(0.025, 0.0, 0.0);
+                // - Vec3::new(0.05, 0.0, 0.0);
+                transform.translation = translation;
+                transform.rotation = Quat::IDENTITY;
+                commands.entity(entity).insert(Holding(hand_entity));
+                commands.entity(entity).insert(RigidBodyDisabled);
+                commands.entity(hand_entity).add_child(entity);
+                grabbed.push(entity);
+                if let (Some(hand), Some(haptic)) = (hand, config.grab_haptic) {
+                    haptics.send(haptic.on(*hand));
                 }
             }
         }
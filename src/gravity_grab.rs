@@ -2,11 +2,16 @@ use bevy::prelude::*;
 use bevy_mod_xr::spaces::XrVelocity;
 use bevy_rapier3d::prelude::*;
 
-use crate::{prelude::*, XrUtilsConfig};
+use crate::{
+    error::{ReportXrError, XrSubsystem, XrUtilsError},
+    prelude::*,
+    XrUtilsConfig,
+};
 
 #[derive(Component)]
-/// Keep track of what entity we are currently gravity grabbing
-pub struct GravityGrabbing;
+/// Keep track of what entity we are currently gravity grabbing, with the tracked space entity
+/// pulling it
+pub struct GravityGrabbing(pub Entity);
 
 #[derive(Component)]
 /// Keep track of what entity we are currently targetting
@@ -35,73 +40,110 @@ fn compute_velocity(hand_transform: Transform, obj_transform: Transform) -> Vec3
 // Detecting if we should launch the entity and when TODO: Fix being able to infinitely float objects
 pub(crate) fn gravity_grabbing(
     mut gravity_query: Query<
-        (&mut Velocity, &mut Transform, Entity),
-        (
-            With<GravityGrabbing>,
-            Without<Holding>,
-            Without<XrTrackedSpace>,
-        ),
+        (&mut Velocity, &mut Transform, Entity, &GravityGrabbing),
+        (Without<Holding>, Without<XrTrackedSpace>),
+    >,
+    hand_query: Query<
+        (&GlobalTransform, &XrVelocity, Option<&Hand>),
+        (With<XrTrackedSpace>, Without<Holding>),
     >,
-    hand_query: Query<(&GlobalTransform, &XrVelocity), (With<XrTrackedSpace>, Without<Holding>)>,
     mut commands: Commands,
     config: Res<XrUtilsConfig>,
     inputs: Option<Res<XrInput>>,
+    mut haptics: EventWriter<XrHapticPulse>,
 ) {
-    if let Some((hand_transform, velocity)) = hand_query.iter().last() {
+    let Some(inputs) = inputs else {
+        return;
+    };
+    for (mut obj_velocity, obj_transform, entity, grabbing) in gravity_query.iter_mut() {
+        let Ok((hand_transform, velocity, hand)) = hand_query.get(grabbing.0) else {
+            // the hand went away
+            commands.entity(entity).remove::<GravityGrabbing>();
+            continue;
+        };
         let hand_transform = hand_transform.compute_transform();
-        if let Ok((mut obj_velocity, obj_transform, entity)) = gravity_query.get_single_mut() {
-            let (squeeze_action, pose_action) = config.gravity_grab_action_names.first().unwrap();
-            if let Some(input) = inputs {
-                let input = input.state.get(squeeze_action).unwrap().as_float().unwrap();
-                if input.cur_val > 0.0 {
-                    // Pick object with hand vel
-                    obj_velocity.linvel = velocity.linear;
-                    let threshold = 0.5;
-
-                    // If we move to fast gravity grab
-                    let magnitude = obj_velocity.linvel.length();
-                    if magnitude > threshold {
-                        let vel = compute_velocity(hand_transform, *obj_transform);
-                        obj_velocity.linvel = vel;
+        let input = match inputs.float_state(&config.grab_action, hand.copied()) {
+            Ok(input) => input,
+            Err(e) => {
+                // missing from the config, turning gravity grab off beats warning every frame
+                commands.queue(ReportXrError::disable(XrSubsystem::GravityGrab, e));
+                return;
+            }
+        };
+        if input.is_pressed {
+            // Pick object with hand vel
+            obj_velocity.linvel = velocity.linear;
+            let threshold = 0.5;
 
-                        commands.entity(entity).remove::<GravityGrabbing>();
-                    }
-                    return;
-                }
+            // If we move to fast gravity grab
+            let magnitude = obj_velocity.linvel.length();
+            if magnitude > threshold {
+                let vel = compute_velocity(hand_transform, *obj_transform);
+                obj_velocity.linvel = vel;
 
                 commands.entity(entity).remove::<GravityGrabbing>();
+                if let (Some(hand), Some(haptic)) = (hand, config.launch_haptic) {
+                    haptics.send(haptic.on(*hand));
+                }
             }
+            continue;
         }
+
+        commands.entity(entity).remove::<GravityGrabbing>();
     }
 }
 // How we actuallly target entities
 // TODO: make some stuff like max distance a resource for the plugin config
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn gesture(
     mut commands: Commands,
-    hand_query: Query<(&GlobalTransform, &XrVelocity), (With<XrTrackedSpace>, Without<Holding>)>,
+    hand_query: Query<
+        (&GlobalTransform, &XrVelocity, Entity, Option<&Hand>),
+        (With<XrTrackedSpace>, Without<Holding>),
+    >,
     mut gravity_query: Query<
         (
             &mut Velocity,
             &mut Transform,
             // &Grabbable,
         ),
-        (Without<Holding>, Without<XrTrackedSpace>, With<Grabbable>),
+        (
+            Without<Holding>,
+            Without<XrTrackedSpace>,
+            Without<GravityGrabbing>,
+            With<Grabbable>,
+        ),
     >,
     holding_query: Query<&Holding>,
     gravity_grabbing: Query<&GravityGrabbing>,
     rapier_context: Query<&RapierContext>,
-    config: Res<XrUtilsConfig>,
     inputs: Option<Res<XrInput>>,
+    contexts: Res<XrInputContexts>,
+    config: Res<XrUtilsConfig>,
 ) {
-    if holding_query.get_single().is_ok() {
+    if hand_query.is_empty() {
         return;
     }
-    if gravity_grabbing.get_single().is_ok() {
+    let Ok(rapier_context) = rapier_context.get_single() else {
+        commands.queue(ReportXrError::disable(
+            XrSubsystem::GravityGrab,
+            XrUtilsError::MissingRapierContext,
+        ));
         return;
-    }
-    if let Some((hand_transform, velocity)) = hand_query.iter().last() {
+    };
+    // the inserts are deferred, keeps both hands from pulling the same entity this frame
+    let mut pulled = Vec::new();
+    for (hand_transform, velocity, hand_entity, hand) in hand_query.iter() {
+        // a hand that's busy doesn't target anything
+        if holding_query.iter().any(|holding| holding.0 == hand_entity)
+            || gravity_grabbing
+                .iter()
+                .any(|grabbing| grabbing.0 == hand_entity)
+        {
+            continue;
+        }
         let hand_transform = hand_transform.compute_transform();
-        if let Some(hit) = rapier_context.get_single().unwrap().cast_shape(
+        let Some(hit) = rapier_context.cast_shape(
             hand_transform.translation,
             Quat::IDENTITY,
             hand_transform.rotation.normalize() * -Vec3::Y,
@@ -113,27 +155,38 @@ pub(crate) fn gesture(
                 compute_impact_geometry_on_penetration: false,
             },
             QueryFilter::only_dynamic(),
-        ) {
-            if let Ok((mut obj_velocity, transform)) = gravity_query.get_mut(hit.0) {
-                // if **grabbable {
-                let distance = hand_transform
-                    .translation
-                    .distance_squared(transform.translation);
-                if distance <= 5.0 {
-                    // So we can get whatever we are currently targetting
-                    commands.entity(hit.0).insert(Targetting);
-                    let (xr_action, pose_action) = config.grab_action_names.first().unwrap();
-                    if let Some(input) = inputs {
-                        let input = input.state.get(xr_action).unwrap().as_float().unwrap();
-
-                        if input.pressed {
-                            obj_velocity.linvel.y = velocity.linear.y;
-                            commands.entity(hit.0).insert(GravityGrabbing);
-                        }
-                    }
-                }
-                // }
+        ) else {
+            continue;
+        };
+        if pulled.contains(&hit.0) {
+            continue;
+        }
+        let Ok((mut obj_velocity, transform)) = gravity_query.get_mut(hit.0) else {
+            continue;
+        };
+        let distance = hand_transform
+            .translation
+            .distance_squared(transform.translation);
+        if distance > 5.0 {
+            continue;
+        }
+        // So we can get whatever we are currently targetting
+        commands.entity(hit.0).insert(Targetting);
+        let Some(inputs) = inputs.as_ref() else {
+            continue;
+        };
+        let input = match inputs.float_state(&config.grab_action, hand.copied()) {
+            Ok(input) => input,
+            Err(e) => {
+                commands.queue(ReportXrError::disable(XrSubsystem::GravityGrab, e));
+                return;
             }
+        };
+
+        if input.pressed && !contexts.is_consumed(&config.grab_action, hand.copied()) {
+            obj_velocity.linvel.y = velocity.linear.y;
+            commands.entity(hit.0).insert(GravityGrabbing(hand_entity));
+            pulled.push(hit.0);
         }
     }
 }
@@ -0,0 +1,133 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use super::{
+    actions::{Hand, XrAction, XrActions},
+    interaction_profile::{XrBoundSource, XrBoundSources},
+};
+
+/// Which action sets get synced and at what priority. Every set is enabled until it's disabled
+/// here, for example with `OnEnter(Menu)` systems that disable "gameplay" and enable "menu".
+///
+/// Priorities decide which set gets an input that's bound in more than one enabled set, the
+/// lower priority actions are made to read as released based on [`XrBoundSources`]. A hand's
+/// state is held at rest once all of its sources are taken, the combined state only once every
+/// source of the action is.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct XrActiveActionSets {
+    disabled: HashSet<String>,
+    priorities: HashMap<String, u32>,
+    /// Actions losing an input to a higher priority set this frame, `None` for the combined state
+    suppressed: HashSet<(XrAction, Option<Hand>)>,
+}
+
+impl XrActiveActionSets {
+    pub fn enable(&mut self, set: &str) {
+        self.disabled.remove(set);
+    }
+
+    pub fn disable(&mut self, set: &str) {
+        self.disabled.insert(set.to_string());
+    }
+
+    pub fn set_enabled(&mut self, set: &str, enabled: bool) {
+        if enabled {
+            self.enable(set);
+        } else {
+            self.disable(set);
+        }
+    }
+
+    pub fn is_enabled(&self, set: &str) -> bool {
+        !self.disabled.contains(set)
+    }
+
+    /// Replaces the priority from the [`Config`](super::config::Config) while the app runs, takes
+    /// effect from the next frame
+    pub fn set_priority(&mut self, set: &str, priority: u32) {
+        self.priorities.insert(set.to_string(), priority);
+    }
+
+    /// Goes back to the priority from the [`Config`](super::config::Config)
+    pub fn reset_priority(&mut self, set: &str) {
+        self.priorities.remove(set);
+    }
+
+    /// The overridden priority of `set` if it has one
+    pub fn priority(&self, set: &str) -> Option<u32> {
+        self.priorities.get(set).copied()
+    }
+
+    /// If `action` is being held at rest because a higher priority set uses the same input
+    pub fn is_suppressed(&self, action: &XrAction, hand: Option<Hand>) -> bool {
+        self.suppressed.contains(&(action.clone(), hand))
+    }
+}
+
+/// Works out which actions lose their input to a higher priority set. Every set is created at
+/// the same priority so the runtime leaves this to us, otherwise it would keep resolving with the
+/// priorities from the [`Config`](super::config::Config) after [`XrActiveActionSets::set_priority`].
+pub fn emulate_set_priorities(
+    actions: Option<Res<XrActions>>,
+    sources: Res<XrBoundSources>,
+    mut active: ResMut<XrActiveActionSets>,
+) {
+    let Some(actions) = actions else {
+        if !active.suppressed.is_empty() {
+            active.bypass_change_detection().suppressed.clear();
+        }
+        return;
+    };
+
+    let priorities: HashMap<&str, u32> = actions
+        .sets()
+        .filter(|(name, _, _)| active.is_enabled(name))
+        .map(|(name, _, priority)| (name, active.priority(name).unwrap_or(priority)))
+        .collect();
+    let action_priority = |action: &XrAction| {
+        actions
+            .set_of(action)
+            .and_then(|set| priorities.get(set).copied())
+    };
+
+    // highest priority each source is bound at
+    let mut highest: HashMap<&str, u32> = HashMap::new();
+    for (action, _) in actions.raw_actions() {
+        let Some(priority) = action_priority(action) else {
+            continue;
+        };
+        for source in sources.get(action) {
+            let entry = highest.entry(source.path.as_str()).or_insert(priority);
+            *entry = (*entry).max(priority);
+        }
+    }
+
+    let mut suppressed = HashSet::new();
+    for (action, _) in actions.raw_actions() {
+        let Some(priority) = action_priority(action) else {
+            continue;
+        };
+        let shadowed = |source: &XrBoundSource| {
+            highest
+                .get(source.path.as_str())
+                .is_some_and(|highest| *highest > priority)
+        };
+        let bound = sources.get(action);
+        // the combined state still reads whatever sources weren't taken
+        if !bound.is_empty() && bound.iter().all(shadowed) {
+            suppressed.insert((action.clone(), None));
+        }
+        for hand in Hand::ALL {
+            let mut on_hand = sources.for_hand(action, hand).peekable();
+            if on_hand.peek().is_some() && on_hand.all(shadowed) {
+                suppressed.insert((action.clone(), Some(hand)));
+            }
+        }
+    }
+    // written without change detection, a change to the sets is what refreshes the sources
+    if active.suppressed != suppressed {
+        active.bypass_change_detection().suppressed = suppressed;
+    }
+}
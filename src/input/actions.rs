@@ -1,4 +1,7 @@
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_mod_openxr::{
     action_binding::OxrSuggestActionBinding,
     action_set_attaching::OxrAttachActionSet,
@@ -16,6 +19,16 @@ use openxr::{Posef, Vector2f};
 // use openxr::{Action, Posef, Vector2f};
 use serde::{Deserialize, Serialize};
 
+use super::{
+    action_sets::XrActiveActionSets,
+    config::{Config, XrActionTiming, XrPressThreshold},
+    contexts::XrInputContexts,
+    joystick::{XrJoystickSettings, XrStickDirection},
+    profiles::{profile_supported, required_extension},
+    rebinding::{layered_config, XrBindingOverrides},
+};
+use crate::error::{ReportXrError, XrSubsystem, XrUtilsError};
+
 // #[derive(Resource)]
 // pub struct XrActions {
 //     HashMap
@@ -31,10 +44,10 @@ pub struct XrAction {
 // FIX: THIS IS JANK like all my code
 impl XrAction {
     /// Does not make a proper action should only be used for getting items from xractions mainly missing pretty name
-    pub fn from_string(string: &String, action_type: &XrActionType) -> Self {
+    pub fn from_string(string: &str, action_type: &XrActionType) -> Self {
         Self {
-            name: string.clone(),
-            pretty_name: string.clone(),
+            name: string.to_string(),
+            pretty_name: string.to_string(),
             action_type: action_type.clone(),
         }
     }
@@ -54,110 +67,218 @@ impl PartialEq for XrAction {
 
 impl Eq for XrAction {}
 
-#[derive(Deserialize, Serialize, Clone)]
-pub struct XrBinding {
-    interaction_profile: String,
-    binding: Vec<String>,
+/// A hand used as an OpenXR subaction path, serialized as the path. A string instead of a
+/// renamed variant so formats like RON that want identifiers for variants can hold it too.
+#[derive(Deserialize, Serialize, Clone, Copy, Component, Debug, PartialEq, Eq, Hash)]
+#[serde(into = "&'static str", try_from = "String")]
+pub enum Hand {
+    Left,
+    Right,
 }
 
-#[derive(Deserialize, Serialize, Clone, Resource)]
-pub struct Config {
-    bindings: Vec<(XrAction, XrBinding)>,
-    set_name: String,
-    set_pretty_name: String,
-}
+impl Hand {
+    pub const ALL: [Hand; 2] = [Hand::Left, Hand::Right];
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            bindings: vec![
-                (
-                    XrAction {
-                        name: "right_pose".to_string(),
-                        pretty_name: "Right Hand Grip Pose".to_string(),
-                        action_type: XrActionType::Pose,
-                    },
-                    XrBinding {
-                        interaction_profile: "/interaction_profiles/oculus/touch_controller".into(),
-                        binding: vec!["/user/hand/right/input/grip/pose".into()],
-                    },
-                ),
-                (
-                    XrAction {
-                        name: "left_pose".to_string(),
-                        pretty_name: "Left Hand Grip Pose".to_string(),
-                        action_type: XrActionType::Pose,
-                    },
-                    XrBinding {
-                        interaction_profile: "/interaction_profiles/oculus/touch_controller".into(),
-                        binding: vec!["/user/hand/left/input/grip/pose".into()],
-                    },
-                ),
-                (
-                    XrAction {
-                        name: "left_joystick".to_string(),
-                        pretty_name: "Left Hand JoyStick".to_string(),
-                        action_type: XrActionType::Vec2,
-                    },
-                    XrBinding {
-                        interaction_profile: "/interaction_profiles/oculus/touch_controller".into(),
-                        binding: vec!["/user/hand/left/input/thumbstick".into()],
-                    },
-                ),
-                (
-                    XrAction {
-                        name: "right_joystick".to_string(),
-                        pretty_name: "Right Hand JoyStick".to_string(),
-                        action_type: XrActionType::Vec2,
-                    },
-                    XrBinding {
-                        interaction_profile: "/interaction_profiles/oculus/touch_controller".into(),
-                        binding: vec!["/user/hand/right/input/thumbstick".into()],
-                    },
-                ),
-                (
-                    XrAction {
-                        name: "right_squeeze".to_string(),
-                        pretty_name: "Right Hand Squeeze".to_string(),
-                        action_type: XrActionType::Float,
-                    },
-                    XrBinding {
-                        interaction_profile: "/interaction_profiles/oculus/touch_controller".into(),
-                        binding: vec!["/user/hand/right/input/squeeze/value".into()],
-                    },
-                ),
-                (
-                    XrAction {
-                        name: "left_squeeze".to_string(),
-                        pretty_name: "Left Hand Squeeze".to_string(),
-                        action_type: XrActionType::Float,
-                    },
-                    XrBinding {
-                        interaction_profile: "/interaction_profiles/oculus/touch_controller".into(),
-                        binding: vec!["/user/hand/left/input/squeeze/value".into()],
-                    },
-                ),
-            ],
-            set_name: "mine".to_string(),
-            set_pretty_name: "My set".to_string(),
+    pub fn path(&self) -> &'static str {
+        match self {
+            Hand::Left => "/user/hand/left",
+            Hand::Right => "/user/hand/right",
         }
     }
 }
 
+impl From<Hand> for &'static str {
+    fn from(hand: Hand) -> Self {
+        hand.path()
+    }
+}
+
+impl TryFrom<String> for Hand {
+    type Error = String;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        Hand::ALL
+            .into_iter()
+            .find(|hand| hand.path() == path)
+            .ok_or_else(|| format!("unknown hand {path}"))
+    }
+}
+
 #[derive(Resource)]
 pub struct XrActions {
-    set: openxr::ActionSet,
+    /// Every set with its name and priority, the main one first
+    sets: Vec<(String, openxr::ActionSet, u32)>,
+    /// The name of the set each action was created in
+    action_sets: HashMap<XrAction, String>,
     actions: HashMap<XrAction, XrRawActionState>,
+    /// The hands each action was declared with as subaction paths
+    subactions: HashMap<XrAction, Vec<Hand>>,
+    hand_paths: HashMap<Hand, openxr::Path>,
+    /// What the actions were created from, to tell which later changes need new ones
+    config: Config,
 }
 
+impl XrActions {
+    pub(crate) fn haptic_actions(
+        &self,
+    ) -> impl Iterator<Item = (&XrAction, &openxr::Action<openxr::Haptic>)> {
+        self.actions.iter().filter_map(|(action, raw)| match raw {
+            XrRawActionState::Haptic(x) => Some((action, x)),
+            _ => None,
+        })
+    }
+
+    /// Every subaction path for `action` along with the hand it belongs to, empty if it has none
+    pub(crate) fn subaction_paths(&self, action: &XrAction) -> Vec<(Hand, openxr::Path)> {
+        self.subactions
+            .get(action)
+            .into_iter()
+            .flatten()
+            .filter_map(|hand| Some((*hand, *self.hand_paths.get(hand)?)))
+            .collect()
+    }
+    pub(crate) fn raw_actions(&self) -> impl Iterator<Item = (&XrAction, &XrRawActionState)> {
+        self.actions.iter()
+    }
+
+    /// Path of the hand's top level user path, `/user/hand/left` or `/user/hand/right`
+    pub(crate) fn hand_path(&self, hand: Hand) -> Option<openxr::Path> {
+        self.hand_paths.get(&hand).copied()
+    }
+
+    /// The main set, the one from [`Config::set_name`]
+    pub(crate) fn set(&self) -> &openxr::ActionSet {
+        &self.sets[0].1
+    }
+
+    /// Name, set and priority from the [`Config`] of every set
+    pub(crate) fn sets(&self) -> impl Iterator<Item = (&str, &openxr::ActionSet, u32)> {
+        self.sets
+            .iter()
+            .map(|(name, set, priority)| (name.as_str(), set, *priority))
+    }
+
+    /// Name of the set `action` belongs to
+    pub(crate) fn set_of(&self, action: &XrAction) -> Option<&str> {
+        self.action_sets.get(action).map(String::as_str)
+    }
+}
+
+/// Inserted once the [`XrActions`] sets are attached to a session. From then on the instance
+/// rejects every binding suggestion, for later sessions too, so it's never removed.
+#[derive(Resource)]
+pub struct XrActionsAttached;
+
+/// The actions or bindings in the [`Config`] changed after they were locked in by
+/// [`XrActionsAttached`], they only apply once the app restarts. Thresholds, timing and joystick
+/// settings still apply right away.
+#[derive(Resource)]
+pub struct XrActionsStale;
+
 #[derive(Resource, Debug)]
 pub struct XrInput {
+    /// State of every action combined across all of its subaction paths
     pub state: HashMap<XrAction, XrActionState>,
+    /// State of actions declared with hand subaction paths, per hand
+    pub hands: HashMap<(XrAction, Hand), XrActionState>,
 }
 
+impl XrInput {
+    pub fn get(&self, action: &XrAction) -> Option<&XrActionState> {
+        self.state.get(action)
+    }
+
+    /// Falls back to the combined state if the action has no subaction path for `hand`
+    pub fn get_hand(&self, action: &XrAction, hand: Hand) -> Option<&XrActionState> {
+        self.hands
+            .get(&(action.clone(), hand))
+            .or_else(|| self.state.get(action))
+    }
+
+    /// Input at rest for every action in `config`, for filling it without a runtime
+    pub fn from_config(config: &Config) -> Self {
+        let mut xr_input = XrInput {
+            state: HashMap::new(),
+            hands: HashMap::new(),
+        };
+        for action_config in config.actions.iter() {
+            let Some(state) = XrActionState::from_type(&action_config.action_type) else {
+                continue;
+            };
+            let action = action_config.action();
+            for hand in action_config.subaction_paths.iter() {
+                xr_input.hands.insert((action.clone(), *hand), state);
+            }
+            xr_input.state.insert(action, state);
+        }
+        xr_input.apply_config(config);
+        xr_input
+    }
+
+    /// Copies the per action settings like [`XrActionTiming`], [`XrPressThreshold`] and
+    /// [`XrJoystickSettings`] from `config` into the states
+    pub fn apply_config(&mut self, config: &Config) {
+        let states = self.state.iter_mut().chain(
+            self.hands
+                .iter_mut()
+                .map(|((action, _), state)| (action, state)),
+        );
+        for (action, state) in states {
+            let Some(action_config) = config
+                .actions
+                .iter()
+                .find(|action_config| action_config.name == action.name)
+            else {
+                continue;
+            };
+            match state {
+                XrActionState::Float(x) => {
+                    x.timer.timing = action_config.timing;
+                    x.threshold = action_config.threshold;
+                }
+                XrActionState::Bool(x) => x.timer.timing = action_config.timing,
+                XrActionState::Vec2(x) => x.settings = action_config.joystick,
+                XrActionState::Pose(_) => {}
+            }
+        }
+    }
+
+    /// Mutable state for `hand`, or the combined one if `hand` is `None`
+    pub fn get_mut(&mut self, action: &XrAction, hand: Option<Hand>) -> Option<&mut XrActionState> {
+        match hand {
+            Some(hand) => self.hands.get_mut(&(action.clone(), hand)),
+            None => self.state.get_mut(action),
+        }
+    }
+
+    /// Float state for `hand`, or the combined one if `hand` is `None`
+    pub fn float_state(
+        &self,
+        action: &XrAction,
+        hand: Option<Hand>,
+    ) -> Result<&XrActionStateFloat, XrUtilsError> {
+        let state = match hand {
+            Some(hand) => self.get_hand(action, hand),
+            None => self.get(action),
+        }
+        .ok_or_else(|| XrUtilsError::MissingAction(action.name.clone()))?;
+        state
+            .as_float()
+            .ok_or_else(|| XrUtilsError::WrongActionType {
+                name: action.name.clone(),
+                expected: XrActionType::Float,
+            })
+    }
+}
+
+/// The view space [`update_head_transforms`] locates the head with, only on the OpenXR rig
 #[derive(Component)]
 pub struct HeadXRSpace(XrReferenceSpace);
 
+/// Marks the entity following the headset, spawned by every backend
+#[derive(Component)]
+pub struct XrHead;
+
 #[derive(Component)]
 pub struct XrTrackedStage;
 
@@ -170,31 +291,71 @@ pub struct XrTrackedView;
 #[derive(Component)]
 pub struct XrSpace;
 
+/// Follows the pose space of the [`XrAction`] on the same entity, add a [`Hand`] to pick the
+/// subaction path, without one it follows the combined pose
 #[derive(Component)]
 pub struct XrTrackedSpace;
 
-pub fn spawn_tracking_rig(actions: Res<XrActions>, mut cmds: Commands, session: Res<OxrSession>) {
+pub fn spawn_tracking_rig(
+    actions: Option<Res<XrActions>>,
+    mut cmds: Commands,
+    session: Res<OxrSession>,
+) {
     //head
-    let head_space = session
+    let head_space = match session
         .create_reference_space(openxr::ReferenceSpaceType::VIEW, Transform::IDENTITY)
-        .unwrap();
+    {
+        Ok(head_space) => head_space,
+        Err(e) => {
+            cmds.queue(ReportXrError::disable(
+                XrSubsystem::TrackingRig,
+                XrUtilsError::Runtime {
+                    what: "failed to create the view space".to_string(),
+                    result: e,
+                },
+            ));
+            return;
+        }
+    };
     cmds.spawn((
         Transform::default(),
         Visibility::default(),
         XrTracker,
         XrVelocity::new(),
+        XrHead,
         HeadXRSpace(head_space),
     ));
 
+    let Some(actions) = actions else {
+        return;
+    };
     for action in actions.actions.iter() {
-        match action.1 {
-            XrRawActionState::Pose(x) => {
-                let space = session
-                    .create_action_space(x, openxr::Path::NULL, Isometry3d::IDENTITY)
-                    .unwrap();
-                cmds.spawn((space, XrSpace, XrVelocity::new(), action.0.clone()));
+        if let XrRawActionState::Pose(x) = action.1 {
+            // the NULL path space is the combined pose, followed by an XrTrackedSpace without a Hand
+            let paths = std::iter::once((None, openxr::Path::NULL)).chain(
+                actions
+                    .subaction_paths(action.0)
+                    .into_iter()
+                    .map(|(hand, path)| (Some(hand), path)),
+            );
+            for (hand, path) in paths {
+                match session.create_action_space(x, path, Isometry3d::IDENTITY) {
+                    Ok(space) => {
+                        let mut entity =
+                            cmds.spawn((space, XrSpace, XrVelocity::new(), action.0.clone()));
+                        if let Some(hand) = hand {
+                            entity.insert(hand);
+                        }
+                    }
+                    Err(e) => cmds.queue(ReportXrError::warn(
+                        XrSubsystem::TrackingRig,
+                        XrUtilsError::Runtime {
+                            what: format!("failed to create a space for {}", action.0.name),
+                            result: e,
+                        },
+                    )),
+                }
             }
-            _ => {}
         }
     }
 }
@@ -203,45 +364,27 @@ pub fn update_inputs(
     inputs: Option<ResMut<XrInput>>,
     actions: Option<Res<XrActions>>,
     session: Option<Res<OxrSession>>,
+    active: Res<XrActiveActionSets>,
 ) {
     if let Some(mut inputs) = inputs {
         if let Some(session) = session {
             if let Some(actions) = actions {
                 for action in actions.actions.iter() {
-                    match action.1 {
-                        XrRawActionState::Float(x) => {
-                            if let Ok(action_new) = x.state(&session, openxr::Path::NULL) {
-                                if let Some(prev_value) = inputs.state.get_mut(&action.0.clone()) {
-                                    let prev_value = prev_value.as_float_mut().unwrap();
-                                    prev_value.pressed = prev_value.previous_val <= 0.0
-                                        && action_new.current_state > 0.0;
-                                    prev_value.cur_val = action_new.current_state;
-                                }
-                            }
-                        }
-                        XrRawActionState::Bool(x) => {
-                            if let Ok(action_new) = x.state(&session, openxr::Path::NULL) {
-                                if let Some(prev_value) = inputs.state.get_mut(&action.0.clone()) {
-                                    let prev_value = prev_value.as_bool_mut().unwrap();
-                                    prev_value.pressed =
-                                        !prev_value.previous_val && action_new.current_state;
-                                    prev_value.cur_val = action_new.current_state;
-                                }
-                            }
+                    if let Some(prev_value) = inputs.state.get_mut(action.0) {
+                        if active.is_suppressed(action.0, None) {
+                            prev_value.release();
+                        } else {
+                            update_action_state(action.1, &session, openxr::Path::NULL, prev_value);
                         }
-                        XrRawActionState::Vec2(x) => {
-                            if let Ok(action_new) = x.state(&session, openxr::Path::NULL) {
-                                if let Some(prev_value) = inputs.state.get_mut(&action.0.clone()) {
-                                    let prev_value = prev_value.as_vec2_mut().unwrap();
-                                    prev_value.cur_val = action_new.current_state.to_vec2();
-                                    prev_value.pressed_x = prev_value.previous_val.x == 0.0
-                                        && action_new.current_state.x != 0.0;
-                                    prev_value.pressed_y = prev_value.previous_val.y == 0.0
-                                        && action_new.current_state.y != 0.0;
-                                }
+                    }
+                    for (hand, path) in actions.subaction_paths(action.0) {
+                        if let Some(prev_value) = inputs.hands.get_mut(&(action.0.clone(), hand)) {
+                            if active.is_suppressed(action.0, Some(hand)) {
+                                prev_value.release();
+                            } else {
+                                update_action_state(action.1, &session, path, prev_value);
                             }
                         }
-                        _ => {}
                     }
                 }
             }
@@ -249,19 +392,72 @@ pub fn update_inputs(
     }
 }
 
-pub fn end_frame_input(inputs: Option<ResMut<XrInput>>) {
+fn update_action_state(
+    raw: &XrRawActionState,
+    session: &OxrSession,
+    path: openxr::Path,
+    prev_value: &mut XrActionState,
+) {
+    match (raw, prev_value) {
+        (XrRawActionState::Float(x), XrActionState::Float(prev_value)) => {
+            if let Ok(action_new) = x.state(session, path) {
+                prev_value.update(action_new.current_state);
+            }
+        }
+        (XrRawActionState::Bool(x), XrActionState::Bool(prev_value)) => {
+            if let Ok(action_new) = x.state(session, path) {
+                prev_value.update(action_new.current_state);
+            }
+        }
+        (XrRawActionState::Vec2(x), XrActionState::Vec2(prev_value)) => {
+            if let Ok(action_new) = x.state(session, path) {
+                prev_value.update(action_new.current_state.to_vec2());
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn tick_action_timers(time: Res<Time>, inputs: Option<ResMut<XrInput>>) {
+    let Some(mut inputs) = inputs else {
+        return;
+    };
+    let delta = time.delta_secs();
+    let inputs = inputs.as_mut();
+    for input in inputs.state.values_mut().chain(inputs.hands.values_mut()) {
+        match input {
+            XrActionState::Float(x) => x.tick(delta),
+            XrActionState::Bool(x) => x.tick(delta),
+            XrActionState::Vec2(_) | XrActionState::Pose(_) => {}
+        }
+    }
+}
+
+pub fn end_frame_input(inputs: Option<ResMut<XrInput>>, contexts: Option<ResMut<XrInputContexts>>) {
+    if let Some(mut contexts) = contexts {
+        if contexts.consumed().next().is_some() {
+            contexts.end_frame();
+        }
+    }
     if let Some(mut inputs) = inputs {
-        for input in inputs.state.iter_mut() {
-            match input.1 {
+        let inputs = inputs.as_mut();
+        for input in inputs.state.values_mut().chain(inputs.hands.values_mut()) {
+            match input {
                 XrActionState::Float(x) => {
                     x.previous_val = x.cur_val;
+                    x.pressed = false;
+                    x.released = false;
                 }
                 XrActionState::Bool(x) => {
                     x.previous_val = x.cur_val;
+                    x.pressed = false;
+                    x.released = false;
                 }
                 XrActionState::Vec2(x) => {
                     x.previous_val = x.cur_val;
+                    x.previous_direction = x.direction;
                 }
+                XrActionState::Pose(_) => {}
             }
         }
     }
@@ -269,17 +465,17 @@ pub fn end_frame_input(inputs: Option<ResMut<XrInput>>) {
 
 pub fn update_spaces(
     mut space_query: Query<
-        (&mut Transform, &XrAction, &XrVelocity),
+        (&mut Transform, &XrAction, &XrVelocity, Option<&Hand>),
         (With<XrSpace>, Without<XrTrackedSpace>),
     >,
     mut tracked_space_query: Query<
-        (&mut Transform, &XrAction, &mut XrVelocity),
+        (&mut Transform, &XrAction, &mut XrVelocity, Option<&Hand>),
         (With<XrTrackedSpace>, Without<XrSpace>),
     >,
 ) {
-    for (space_transform, space_action, space_velocity) in space_query.iter_mut() {
-        for (mut transform, action, mut velocity) in tracked_space_query.iter_mut() {
-            if action == space_action {
+    for (space_transform, space_action, space_velocity, space_hand) in space_query.iter_mut() {
+        for (mut transform, action, mut velocity, hand) in tracked_space_query.iter_mut() {
+            if action == space_action && hand == space_hand {
                 *transform = *space_transform;
                 *velocity = *space_velocity;
             }
@@ -299,6 +495,18 @@ pub fn update_stage(
     }
 }
 
+/// When the frame being prepared will be displayed
+fn predicted_time(pipelined: bool, frame_state: &OxrFrameState) -> openxr::Time {
+    if pipelined {
+        openxr::Time::from_nanos(
+            frame_state.predicted_display_time.as_nanos()
+                + frame_state.predicted_display_period.as_nanos(),
+        )
+    } else {
+        frame_state.predicted_display_time
+    }
+}
+
 pub fn update_head_transforms(
     session: Res<OxrSession>,
     default_ref_space: Res<XrPrimaryReferenceSpace>,
@@ -308,14 +516,7 @@ pub fn update_head_transforms(
 ) {
     for (mut transform, space, ref_space) in &mut query {
         let ref_space = ref_space.unwrap_or(&default_ref_space);
-        let time = if pipelined.is_some() {
-            openxr::Time::from_nanos(
-                frame_state.predicted_display_time.as_nanos()
-                    + frame_state.predicted_display_period.as_nanos(),
-            )
-        } else {
-            frame_state.predicted_display_time
-        };
+        let time = predicted_time(pipelined.is_some(), &frame_state);
         let space_location = session.locate_space(&space.0, ref_space, time);
 
         if let Ok(space_location) = space_location {
@@ -330,9 +531,108 @@ pub fn update_head_transforms(
     }
 }
 
+/// Fills the pose states in [`XrInput`] from the action spaces spawned by [`spawn_tracking_rig`].
+/// The combined state comes from the NULL path space, or from a tracked hand if the runtime
+/// wouldn't create that one.
+#[allow(clippy::too_many_arguments)]
+pub fn update_pose_states(
+    inputs: Option<ResMut<XrInput>>,
+    actions: Option<Res<XrActions>>,
+    session: Res<OxrSession>,
+    default_ref_space: Res<XrPrimaryReferenceSpace>,
+    pipelined: Option<Res<Pipelined>>,
+    frame_state: Res<OxrFrameState>,
+    space_query: Query<
+        (
+            &bevy_mod_xr::spaces::XrSpace,
+            &XrAction,
+            Option<&Hand>,
+            Option<&XrReferenceSpace>,
+        ),
+        With<XrSpace>,
+    >,
+) {
+    let (Some(mut inputs), Some(actions)) = (inputs, actions) else {
+        return;
+    };
+    let time = predicted_time(pipelined.is_some(), &frame_state);
+    let mut combined = HashSet::new();
+    for (space, action, hand, ref_space) in space_query.iter() {
+        let Some(XrRawActionState::Pose(raw)) = actions.actions.get(action) else {
+            continue;
+        };
+        if hand.is_none() {
+            combined.insert(action);
+        }
+        let path = match hand {
+            Some(hand) => match actions.hand_paths.get(hand) {
+                Some(path) => *path,
+                None => continue,
+            },
+            None => openxr::Path::NULL,
+        };
+        let Some(XrActionState::Pose(state)) = inputs.get_mut(action, hand.copied()) else {
+            continue;
+        };
+        state.is_active = raw.is_active(&session, path).unwrap_or(false);
+        let ref_space = ref_space.unwrap_or(&default_ref_space);
+        let Ok(space_location) = session.locate_space(space, ref_space, time) else {
+            state.position_valid = false;
+            state.orientation_valid = false;
+            state.position_tracked = false;
+            state.orientation_tracked = false;
+            continue;
+        };
+        let flags = OxrSpaceLocationFlags(space_location.location_flags);
+        state.position_valid = flags.pos_valid();
+        state.orientation_valid = flags.rot_valid();
+        state.position_tracked = flags.pos_tracked();
+        state.orientation_tracked = flags.rot_tracked();
+        if flags.pos_valid() {
+            state.local.translation = space_location.pose.position.to_vec3();
+        }
+        if flags.rot_valid() {
+            state.local.rotation = space_location.pose.orientation.to_quat();
+        }
+    }
+
+    for (action, raw) in actions.actions.iter() {
+        if !matches!(raw, XrRawActionState::Pose(_)) || combined.contains(action) {
+            continue;
+        }
+        let from_hand = actions
+            .subaction_paths(action)
+            .into_iter()
+            .filter_map(|(hand, _)| inputs.get_hand(action, hand)?.as_pose().copied())
+            .find(|state| state.is_active && state.position_valid);
+        if let (Some(from_hand), Some(XrActionState::Pose(state))) =
+            (from_hand, inputs.get_mut(action, None))
+        {
+            *state = from_hand;
+        }
+    }
+}
+
+/// Places every pose state under the [`XrTrackingRoot`], runs after all backends filled `local`
+pub fn update_pose_world(
+    inputs: Option<ResMut<XrInput>>,
+    root_query: Query<&Transform, With<XrTrackingRoot>>,
+) {
+    let Some(mut inputs) = inputs else {
+        return;
+    };
+    let root = root_query.get_single().copied().unwrap_or_default();
+    let inputs = inputs.as_mut();
+    for input in inputs.state.values_mut().chain(inputs.hands.values_mut()) {
+        if let XrActionState::Pose(x) = input {
+            x.world = root * x.local;
+        }
+    }
+}
+
 pub fn update_view(
-    mut head_query: Query<&mut Transform, (With<HeadXRSpace>, Without<XrTrackedView>)>,
-    mut view_query: Query<&mut Transform, (With<XrTrackedView>, Without<HeadXRSpace>)>,
+    mut head_query: Query<&mut Transform, (With<XrHead>, Without<XrTrackedView>)>,
+    mut view_query: Query<&mut Transform, (With<XrTrackedView>, Without<XrHead>)>,
 ) {
     let head_transform = head_query.get_single_mut();
     if let Ok(root) = head_transform {
@@ -343,8 +643,8 @@ pub fn update_view(
 }
 
 pub fn update_local_floor_transforms(
-    mut head_space: Query<&mut Transform, (With<HeadXRSpace>, Without<XrTrackedLocalFloor>)>,
-    mut local_floor: Query<&mut Transform, (With<XrTrackedLocalFloor>, Without<HeadXRSpace>)>,
+    mut head_space: Query<&mut Transform, (With<XrHead>, Without<XrTrackedLocalFloor>)>,
+    mut local_floor: Query<&mut Transform, (With<XrTrackedLocalFloor>, Without<XrHead>)>,
 ) {
     let head_transform = head_space.get_single_mut();
     if let Ok(head) = head_transform {
@@ -360,194 +660,314 @@ pub fn update_local_floor_transforms(
     }
 }
 
-pub fn create_input(actions: Res<XrActions>, mut cmds: Commands, session: Res<OxrSession>) {
+pub fn create_input(
+    actions: Res<XrActions>,
+    config: Res<Config>,
+    mut cmds: Commands,
+    session: Res<OxrSession>,
+) {
     let mut xr_input = XrInput {
         state: HashMap::new(),
+        hands: HashMap::new(),
     };
     for action in actions.actions.iter() {
-        match action.1 {
-            XrRawActionState::Float(x) => {
-                if let Ok(action_new) = x.state(&session, openxr::Path::NULL) {
-                    xr_input.state.insert(
-                        action.0.clone(),
-                        XrActionState::Float(XrActionStateFloat {
-                            previous_val: 0.0,
-                            cur_val: action_new.current_state,
-                            pressed: false,
-                        }),
-                    );
-                    // .unwrap();
-                }
-            }
-            XrRawActionState::Bool(x) => {
-                if let Ok(action_new) = x.state(&session, openxr::Path::NULL) {
-                    xr_input.state.insert(
-                        action.0.clone(),
-                        XrActionState::Bool(XrActionStateBool {
-                            previous_val: false,
-                            cur_val: action_new.current_state,
-                            pressed: false,
-                        }),
-                    );
-                }
-            }
-            XrRawActionState::Vec2(x) => {
-                if let Ok(action_new) = x.state(&session, openxr::Path::NULL) {
-                    xr_input.state.insert(
-                        action.0.clone(),
-                        XrActionState::Vec2(XrActionStateVec2 {
-                            previous_val: Vec2::ZERO,
-                            cur_val: action_new.current_state.to_vec2(),
-                            pressed_x: false,
-                            pressed_y: false,
-                        }),
-                    );
-                }
+        if let Some(state) = initial_action_state(action.1, &session, openxr::Path::NULL) {
+            xr_input.state.insert(action.0.clone(), state);
+        }
+        for (hand, path) in actions.subaction_paths(action.0) {
+            if let Some(state) = initial_action_state(action.1, &session, path) {
+                xr_input.hands.insert((action.0.clone(), hand), state);
             }
-            _ => {}
         }
     }
+    xr_input.apply_config(&config);
     cmds.insert_resource(xr_input);
 }
 
-pub fn sync_actions(actions: Res<XrActions>, mut sync: EventWriter<OxrSyncActionSet>) {
-    sync.send(OxrSyncActionSet(actions.set.clone()));
+fn initial_action_state(
+    raw: &XrRawActionState,
+    session: &OxrSession,
+    path: openxr::Path,
+) -> Option<XrActionState> {
+    match raw {
+        XrRawActionState::Float(x) => {
+            let action_new = x.state(session, path).ok()?;
+            Some(XrActionState::Float(XrActionStateFloat {
+                previous_val: 0.0,
+                cur_val: action_new.current_state,
+                ..default()
+            }))
+        }
+        XrRawActionState::Bool(x) => {
+            let action_new = x.state(session, path).ok()?;
+            Some(XrActionState::Bool(XrActionStateBool {
+                previous_val: false,
+                cur_val: action_new.current_state,
+                ..default()
+            }))
+        }
+        XrRawActionState::Vec2(x) => {
+            let action_new = x.state(session, path).ok()?;
+            Some(XrActionState::Vec2(XrActionStateVec2 {
+                previous_val: Vec2::ZERO,
+                cur_val: action_new.current_state.to_vec2(),
+                raw_val: action_new.current_state.to_vec2(),
+                ..default()
+            }))
+        }
+        XrRawActionState::Pose(_) => Some(XrActionState::Pose(XrActionStatePose::default())),
+        XrRawActionState::Haptic(_) => None,
+    }
 }
 
-pub fn attach_set(actions: Res<XrActions>, mut attach: EventWriter<OxrAttachActionSet>) {
-    attach.send(OxrAttachActionSet(actions.set.clone()));
+/// Syncs the enabled sets, priorities between them are handled by
+/// [`emulate_set_priorities`](super::action_sets::emulate_set_priorities)
+pub fn sync_actions(
+    actions: Option<Res<XrActions>>,
+    active: Res<XrActiveActionSets>,
+    mut sync: EventWriter<OxrSyncActionSet>,
+) {
+    let Some(actions) = actions else {
+        return;
+    };
+    for (name, set, _) in actions.sets() {
+        if active.is_enabled(name) {
+            sync.send(OxrSyncActionSet(set.clone()));
+        }
+    }
+}
+
+pub fn attach_set(
+    actions: Option<Res<XrActions>>,
+    mut attach: EventWriter<OxrAttachActionSet>,
+    mut cmds: Commands,
+) {
+    if let Some(actions) = actions {
+        for (_, set, _) in actions.sets() {
+            attach.send(OxrAttachActionSet(set.clone()));
+        }
+        cmds.insert_resource(XrActionsAttached);
+    }
 }
 
 pub fn suggest_action_bindings(
-    actions: Res<XrActions>,
+    actions: Option<Res<XrActions>>,
+    attached: Option<Res<XrActionsAttached>>,
+    instance: Res<OxrInstance>,
     config: Res<Config>,
+    overrides: Option<Res<XrBindingOverrides>>,
     mut bindings: EventWriter<OxrSuggestActionBinding>,
+    mut cmds: Commands,
 ) {
-    for binding in config.bindings.clone() {
-        match actions.actions.get(&binding.0).unwrap() {
-            XrRawActionState::Float(x) => {
-                bindings.send(OxrSuggestActionBinding {
-                    action: x.as_raw(),
-                    interaction_profile: binding.1.interaction_profile.into(),
-                    bindings: binding
-                        .1
-                        .binding
-                        .iter()
-                        .cloned()
-                        .map(|a| Into::<std::borrow::Cow<'static, str>>::into(a))
-                        .collect(),
-                });
-            }
-            XrRawActionState::Bool(x) => {
-                bindings.send(OxrSuggestActionBinding {
-                    action: x.as_raw(),
-                    interaction_profile: binding.1.interaction_profile.into(),
-                    bindings: binding
-                        .1
-                        .binding
-                        .iter()
-                        .cloned()
-                        .map(|a| Into::<std::borrow::Cow<'static, str>>::into(a))
-                        .collect(),
-                });
-            }
-            XrRawActionState::Pose(x) => {
-                bindings.send(OxrSuggestActionBinding {
-                    action: x.as_raw(),
-                    interaction_profile: binding.1.interaction_profile.into(),
-                    bindings: binding
-                        .1
-                        .binding
-                        .iter()
-                        .cloned()
-                        .map(|a| Into::<std::borrow::Cow<'static, str>>::into(a))
-                        .collect(),
-                });
-            }
-            XrRawActionState::Vec2(x) => {
-                bindings.send(OxrSuggestActionBinding {
-                    action: x.as_raw(),
-                    interaction_profile: binding.1.interaction_profile.into(),
-                    bindings: binding
-                        .1
-                        .binding
-                        .iter()
-                        .cloned()
-                        .map(|a| Into::<std::borrow::Cow<'static, str>>::into(a))
-                        .collect(),
-                });
+    let Some(actions) = actions else {
+        return;
+    };
+    if attached.is_some() {
+        // the runtime would answer XR_ERROR_ACTIONSETS_ALREADY_ATTACHED
+        return;
+    }
+    let config = layered_config(&config, overrides.as_deref());
+    suggest_bindings(&actions, &config, &instance, &mut bindings, &mut cmds);
+}
+
+/// Suggests every binding in `config`, skipping profiles whose extension isn't enabled
+pub(crate) fn suggest_bindings(
+    actions: &XrActions,
+    config: &Config,
+    instance: &OxrInstance,
+    bindings: &mut EventWriter<OxrSuggestActionBinding>,
+    cmds: &mut Commands,
+) {
+    for action_config in config.actions.iter() {
+        let Some(action) = actions.actions.get(&action_config.action()) else {
+            cmds.queue(ReportXrError::warn(
+                XrSubsystem::Actions,
+                XrUtilsError::MissingAction(action_config.name.clone()),
+            ));
+            continue;
+        };
+        for binding in action_config.bindings.iter() {
+            if !profile_supported(&binding.interaction_profile, instance) {
+                debug!(
+                    "skipping {} bindings for {}, {} isn't enabled",
+                    binding.interaction_profile,
+                    action_config.name,
+                    required_extension(&binding.interaction_profile).unwrap_or_default()
+                );
+                continue;
             }
+            bindings.send(OxrSuggestActionBinding {
+                action: action.as_raw(),
+                interaction_profile: binding.interaction_profile.clone().into(),
+                bindings: binding
+                    .binding
+                    .iter()
+                    .cloned()
+                    .map(Into::<std::borrow::Cow<'static, str>>::into)
+                    .collect(),
+            });
         }
     }
 }
-pub fn create_actions(instance: Res<OxrInstance>, mut cmds: Commands, config: Res<Config>) {
-    cmds.insert_resource(XrActions::from_config(config.clone(), &instance));
+
+pub fn create_actions(
+    instance: Res<OxrInstance>,
+    mut cmds: Commands,
+    config: Res<Config>,
+    overrides: Option<Res<XrBindingOverrides>>,
+) {
+    let config = layered_config(&config, overrides.as_deref());
+    match XrActions::from_config(&config, &instance) {
+        Ok(actions) => cmds.insert_resource(actions),
+        Err(e) => cmds.queue(ReportXrError::disable(XrSubsystem::Actions, e)),
+    }
+}
+
+/// Rebuilds [`XrActions`] when the actions or bindings in the [`Config`] or the
+/// [`XrBindingOverrides`] change, everything else is applied to [`XrInput`] as it is. Bindings
+/// can only be suggested until a set is attached to a session, after that the running actions are
+/// kept and [`XrActionsStale`] marks that the change waits for a restart.
+pub fn reload_actions(
+    config: Res<Config>,
+    overrides: Option<Res<XrBindingOverrides>>,
+    instance: Option<Res<OxrInstance>>,
+    actions: Option<Res<XrActions>>,
+    attached: Option<Res<XrActionsAttached>>,
+    input: Option<ResMut<XrInput>>,
+    mut cmds: Commands,
+) {
+    let config_changed = config.is_changed() && !config.is_added();
+    let overrides_changed = overrides
+        .as_ref()
+        .is_some_and(|overrides| overrides.is_changed() && !overrides.is_added());
+    if !config_changed && !overrides_changed {
+        return;
+    }
+    let config = layered_config(&config, overrides.as_deref());
+    if let Some(mut input) = input {
+        input.apply_config(&config);
+    }
+    let (Some(actions), Some(instance)) = (actions, instance) else {
+        return;
+    };
+    if actions.config.same_actions(&config) {
+        return;
+    }
+    if attached.is_some() {
+        cmds.insert_resource(XrActionsStale);
+        cmds.queue(ReportXrError::warn(
+            XrSubsystem::Actions,
+            XrUtilsError::RestartRequired,
+        ));
+        return;
+    }
+    rebuild_actions(&config, &instance, &mut cmds);
+}
+
+/// Tears down everything tied to the ending session, the actions stay for the next one
+pub fn teardown_actions(
+    rig_query: Query<Entity, Or<(With<XrHead>, With<XrSpace>)>>,
+    mut cmds: Commands,
+) {
+    for entity in rig_query.iter() {
+        cmds.entity(entity).despawn_recursive();
+    }
+    cmds.remove_resource::<XrInput>();
+}
+
+fn rebuild_actions(config: &Config, instance: &OxrInstance, cmds: &mut Commands) {
+    match XrActions::from_config(config, instance) {
+        Ok(actions) => {
+            cmds.insert_resource(actions);
+            cmds.remove_resource::<XrInput>();
+        }
+        Err(e) => cmds.queue(ReportXrError::disable(XrSubsystem::Actions, e)),
+    }
 }
 
 impl XrActions {
-    fn from_config(config: Config, instance: &OxrInstance) -> Self {
-        let set = instance
-            .create_action_set(config.set_name.as_str(), config.set_pretty_name.as_str(), 0)
-            .unwrap();
-        let mut actions = HashMap::new();
-        for binding in config.bindings.clone() {
-            match binding.0.action_type {
-                XrActionType::Float => {
-                    actions.insert(
-                        binding.0.clone(),
-                        XrRawActionState::Float(
-                            set.create_action::<f32>(
-                                binding.0.name.as_str(),
-                                binding.0.pretty_name.as_str(),
-                                &[],
-                            )
-                            .unwrap(),
-                        ),
-                    );
-                }
-                XrActionType::Bool => {
-                    actions.insert(
-                        binding.0.clone(),
-                        XrRawActionState::Bool(
-                            set.create_action::<bool>(
-                                binding.0.name.as_str(),
-                                binding.0.pretty_name.as_str(),
-                                &[],
-                            )
-                            .unwrap(),
-                        ),
-                    );
-                }
-                XrActionType::Pose => {
-                    actions.insert(
-                        binding.0.clone(),
-                        XrRawActionState::Pose(
-                            set.create_action::<Posef>(
-                                binding.0.name.as_str(),
-                                binding.0.pretty_name.as_str(),
-                                &[],
-                            )
-                            .unwrap(),
-                        ),
-                    );
-                }
-                XrActionType::Vec2 => {
-                    actions.insert(
-                        binding.0.clone(),
-                        XrRawActionState::Vec2(
-                            set.create_action::<Vector2f>(
-                                binding.0.name.as_str(),
-                                binding.0.pretty_name.as_str(),
-                                &[],
-                            )
-                            .unwrap(),
-                        ),
-                    );
+    pub fn from_config(config: &Config, instance: &OxrInstance) -> Result<Self, XrUtilsError> {
+        config.validate().map_err(XrUtilsError::InvalidConfig)?;
+        let mut sets = Vec::new();
+        let set_configs =
+            std::iter::once((&config.set_name, &config.set_pretty_name, config.priority)).chain(
+                config
+                    .sets
+                    .iter()
+                    .map(|set| (&set.name, &set.pretty_name, set.priority)),
+            );
+        for (name, pretty_name, priority) in set_configs {
+            // every set is the same to the runtime so it never suppresses anything itself,
+            // priorities can change while the app runs and only the emulation sees that
+            let set = instance
+                .create_action_set(name.as_str(), pretty_name.as_str(), 0)
+                .map_err(|result| XrUtilsError::CreateActionSet {
+                    name: name.clone(),
+                    result,
+                })?;
+            sets.push((name.clone(), set, priority));
+        }
+        let mut hand_paths = HashMap::new();
+        for hand in Hand::ALL {
+            let path = instance.string_to_path(hand.path()).map_err(|result| {
+                XrUtilsError::InvalidPath {
+                    path: hand.path().to_string(),
+                    result,
                 }
+            })?;
+            hand_paths.insert(hand, path);
+        }
+        let mut actions = HashMap::new();
+        let mut subactions = HashMap::new();
+        let mut action_sets = HashMap::new();
+        for action_config in config.actions.iter() {
+            let action = action_config.action();
+            let set_name = action_config.set.as_ref().unwrap_or(&config.set_name);
+            let (_, set, _) = sets
+                .iter()
+                .find(|(name, _, _)| name == set_name)
+                .unwrap_or(&sets[0]);
+            let name = action.name.as_str();
+            let pretty_name = action.pretty_name.as_str();
+            let paths: Vec<openxr::Path> = action_config
+                .subaction_paths
+                .iter()
+                .map(|hand| hand_paths[hand])
+                .collect();
+            let raw = match action.action_type {
+                XrActionType::Float => set
+                    .create_action::<f32>(name, pretty_name, &paths)
+                    .map(XrRawActionState::Float),
+                XrActionType::Bool => set
+                    .create_action::<bool>(name, pretty_name, &paths)
+                    .map(XrRawActionState::Bool),
+                XrActionType::Pose => set
+                    .create_action::<Posef>(name, pretty_name, &paths)
+                    .map(XrRawActionState::Pose),
+                XrActionType::Vec2 => set
+                    .create_action::<Vector2f>(name, pretty_name, &paths)
+                    .map(XrRawActionState::Vec2),
+                XrActionType::Haptic => set
+                    .create_action::<openxr::Haptic>(name, pretty_name, &paths)
+                    .map(XrRawActionState::Haptic),
             }
+            .map_err(|result| XrUtilsError::CreateAction {
+                name: action.name.clone(),
+                result,
+            })?;
+            subactions.insert(action.clone(), action_config.subaction_paths.clone());
+            action_sets.insert(action.clone(), set_name.clone());
+            actions.insert(action, raw);
         }
 
-        return Self { set, actions };
+        Ok(Self {
+            sets,
+            action_sets,
+            actions,
+            subactions,
+            hand_paths,
+            config: config.clone(),
+        })
     }
 }
 
@@ -557,6 +977,7 @@ pub enum XrActionType {
     Vec2,
     Bool,
     Pose,
+    Haptic,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -564,6 +985,7 @@ pub enum XrActionState {
     Float(XrActionStateFloat),
     Bool(XrActionStateBool),
     Vec2(XrActionStateVec2),
+    Pose(XrActionStatePose),
 }
 
 impl XrActionState {
@@ -603,21 +1025,68 @@ impl XrActionState {
             _ => None,
         }
     }
+    pub fn as_pose(&self) -> Option<&XrActionStatePose> {
+        match self {
+            XrActionState::Pose(x) => Some(x),
+            _ => None,
+        }
+    }
+    pub fn as_pose_mut(&mut self) -> Option<&mut XrActionStatePose> {
+        match self {
+            XrActionState::Pose(x) => Some(x),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct XrActionStateFloat {
     pub previous_val: f32,
     pub cur_val: f32,
+    /// Held down according to `threshold`
+    pub is_pressed: bool,
     pub pressed: bool,
+    pub released: bool,
+    pub threshold: XrPressThreshold,
+    pub timer: XrPressTimer,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct XrActionStateVec2 {
     pub previous_val: Vec2,
+    /// After the deadzones and response curve in `settings`
     pub cur_val: Vec2,
+    /// As read from the runtime
+    pub raw_val: Vec2,
     pub pressed_x: bool,
     pub pressed_y: bool,
+    pub direction: Option<XrStickDirection>,
+    pub previous_direction: Option<XrStickDirection>,
+    pub settings: XrJoystickSettings,
+}
+
+/// Where a pose action is and how much of that the runtime is sure of
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XrActionStatePose {
+    /// Bound to a device that's currently giving input
+    pub is_active: bool,
+    pub position_valid: bool,
+    pub orientation_valid: bool,
+    /// The position is tracked rather than estimated
+    pub position_tracked: bool,
+    /// The orientation is tracked rather than estimated
+    pub orientation_tracked: bool,
+    /// Relative to the reference space, the same as the pose space's [`Transform`]
+    pub local: Transform,
+    /// Placed under the [`XrTrackingRoot`]
+    pub world: Transform,
+}
+
+impl XrActionStatePose {
+    /// Tracked well enough to show a controller at `world`
+    pub fn is_tracked(&self) -> bool {
+        self.is_active && self.position_tracked && self.orientation_tracked
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -625,6 +1094,153 @@ pub struct XrActionStateBool {
     pub previous_val: bool,
     pub cur_val: bool,
     pub pressed: bool,
+    pub released: bool,
+    pub timer: XrPressTimer,
+}
+
+/// Hold, long press and double tap tracking shared by float and bool actions, advanced by
+/// [`tick_action_timers`] after the values for the frame are in
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XrPressTimer {
+    pub timing: XrActionTiming,
+    /// Seconds since the action was pressed, 0.0 while released
+    pub held_for: f32,
+    /// Set for the frame `held_for` passes the long press threshold
+    pub long_pressed: bool,
+    /// Set for the frame of a press that came within the double tap window of the last one
+    pub double_tapped: bool,
+    /// Seconds since the last press that could still start a double tap
+    pub since_press: Option<f32>,
+}
+
+impl XrPressTimer {
+    fn tick(&mut self, pressed: bool, active: bool, delta: f32) {
+        self.long_pressed = false;
+        self.double_tapped = false;
+        if let Some(since_press) = self.since_press.as_mut() {
+            *since_press += delta;
+        }
+        if pressed {
+            self.held_for = 0.0;
+            self.double_tapped = self
+                .since_press
+                .is_some_and(|since_press| since_press <= self.timing.double_tap);
+            // a third tap starts a new pair instead of double tapping again
+            self.since_press = if self.double_tapped { None } else { Some(0.0) };
+        } else if active {
+            let before = self.held_for;
+            self.held_for += delta;
+            self.long_pressed =
+                before < self.timing.long_press && self.held_for >= self.timing.long_press;
+        } else {
+            self.held_for = 0.0;
+        }
+    }
+}
+
+impl XrActionStateFloat {
+    /// Sets the value read this frame
+    pub fn update(&mut self, value: f32) {
+        let was_pressed = self.is_pressed;
+        self.is_pressed = if was_pressed {
+            value > self.threshold.release
+        } else {
+            value > self.threshold.press
+        };
+        self.pressed = !was_pressed && self.is_pressed;
+        self.released = was_pressed && !self.is_pressed;
+        self.cur_val = value;
+    }
+
+    /// Advances the hold and tap timers by `delta` seconds
+    pub fn tick(&mut self, delta: f32) {
+        self.timer.tick(self.pressed, self.is_pressed, delta);
+    }
+
+    pub fn held_for(&self) -> f32 {
+        self.timer.held_for
+    }
+
+    pub fn long_pressed(&self) -> bool {
+        self.timer.long_pressed
+    }
+
+    pub fn double_tapped(&self) -> bool {
+        self.timer.double_tapped
+    }
+}
+
+impl XrActionStateVec2 {
+    /// Sets the value read this frame
+    pub fn update(&mut self, value: Vec2) {
+        self.raw_val = value;
+        let value = self.settings.process(value);
+        self.pressed_x = self.previous_val.x == 0.0 && value.x != 0.0;
+        self.pressed_y = self.previous_val.y == 0.0 && value.y != 0.0;
+        self.cur_val = value;
+        self.direction = self.settings.direction(value, self.direction);
+    }
+
+    /// The direction the stick started pointing in this frame
+    pub fn direction_pressed(&self) -> Option<XrStickDirection> {
+        self.direction
+            .filter(|_| self.direction != self.previous_direction)
+    }
+
+    /// The direction the stick stopped pointing in this frame
+    pub fn direction_released(&self) -> Option<XrStickDirection> {
+        self.previous_direction
+            .filter(|_| self.direction != self.previous_direction)
+    }
+}
+
+impl XrActionStateBool {
+    /// Sets the value read this frame
+    pub fn update(&mut self, value: bool) {
+        self.pressed = !self.previous_val && value;
+        self.released = self.previous_val && !value;
+        self.cur_val = value;
+    }
+
+    /// Advances the hold and tap timers by `delta` seconds
+    pub fn tick(&mut self, delta: f32) {
+        self.timer.tick(self.pressed, self.cur_val, delta);
+    }
+
+    pub fn held_for(&self) -> f32 {
+        self.timer.held_for
+    }
+
+    pub fn long_pressed(&self) -> bool {
+        self.timer.long_pressed
+    }
+
+    pub fn double_tapped(&self) -> bool {
+        self.timer.double_tapped
+    }
+}
+
+impl XrActionState {
+    /// Updates the state as if the input was let go, poses are left alone
+    pub fn release(&mut self) {
+        match self {
+            XrActionState::Float(x) => x.update(0.0),
+            XrActionState::Bool(x) => x.update(false),
+            XrActionState::Vec2(x) => x.update(Vec2::ZERO),
+            XrActionState::Pose(_) => {}
+        }
+    }
+
+    /// Resting state for an action of `action_type`, `None` for haptics which have no state
+    pub fn from_type(action_type: &XrActionType) -> Option<Self> {
+        match action_type {
+            XrActionType::Float => Some(XrActionState::Float(XrActionStateFloat::default())),
+            XrActionType::Bool => Some(XrActionState::Bool(XrActionStateBool::default())),
+            XrActionType::Vec2 => Some(XrActionState::Vec2(XrActionStateVec2::default())),
+            XrActionType::Pose => Some(XrActionState::Pose(XrActionStatePose::default())),
+            XrActionType::Haptic => None,
+        }
+    }
 }
 
 pub enum XrRawActionState {
@@ -632,4 +1248,121 @@ pub enum XrRawActionState {
     Vec2(openxr::Action<openxr::Vector2f>),
     Bool(openxr::Action<bool>),
     Pose(openxr::Action<openxr::Posef>),
+    Haptic(openxr::Action<openxr::Haptic>),
+}
+
+impl XrRawActionState {
+    pub fn as_raw(&self) -> openxr::sys::Action {
+        match self {
+            XrRawActionState::Float(x) => x.as_raw(),
+            XrRawActionState::Vec2(x) => x.as_raw(),
+            XrRawActionState::Bool(x) => x.as_raw(),
+            XrRawActionState::Pose(x) => x.as_raw(),
+            XrRawActionState::Haptic(x) => x.as_raw(),
+        }
+    }
+
+    /// Input source paths the runtime currently binds the action to
+    pub fn bound_sources(&self, session: &OxrSession) -> openxr::Result<Vec<openxr::Path>> {
+        match self {
+            XrRawActionState::Float(x) => x.bound_sources(session),
+            XrRawActionState::Vec2(x) => x.bound_sources(session),
+            XrRawActionState::Bool(x) => x.bound_sources(session),
+            XrRawActionState::Pose(x) => x.bound_sources(session),
+            XrRawActionState::Haptic(x) => x.bound_sources(session),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exact in binary so the thresholds are hit on a known frame
+    const DELTA: f32 = 0.125;
+
+    /// One frame the way the input systems run it
+    fn frame(state: &mut XrActionStateBool, value: bool) {
+        state.update(value);
+        state.tick(DELTA);
+        state.previous_val = state.cur_val;
+    }
+
+    #[test]
+    fn long_press_fires_once() {
+        let mut state = XrActionStateBool::default();
+        frame(&mut state, true);
+        let long_pressed: Vec<bool> = (0..6)
+            .map(|_| {
+                frame(&mut state, true);
+                state.long_pressed()
+            })
+            .collect();
+        // the default long press is 0.5 seconds
+        assert_eq!(long_pressed, [false, false, false, true, false, false]);
+        assert_eq!(state.held_for(), 0.75);
+        frame(&mut state, false);
+        assert_eq!(state.held_for(), 0.0);
+        assert!(!state.long_pressed());
+    }
+
+    #[test]
+    fn double_tap_within_the_window() {
+        let mut state = XrActionStateBool::default();
+        let taps: Vec<bool> = [true, false, true, false, true]
+            .into_iter()
+            .map(|value| {
+                frame(&mut state, value);
+                state.double_tapped()
+            })
+            .collect();
+        // the third press starts a new pair
+        assert_eq!(taps, [false, false, true, false, false]);
+    }
+
+    #[test]
+    fn slow_taps_dont_double_tap() {
+        let mut state = XrActionStateBool::default();
+        frame(&mut state, true);
+        // 0.5 seconds between the presses, the default window is 0.3
+        for _ in 0..3 {
+            frame(&mut state, false);
+        }
+        frame(&mut state, true);
+        assert!(!state.double_tapped());
+    }
+
+    #[test]
+    fn float_threshold_has_hysteresis() {
+        let mut state = XrActionStateFloat {
+            threshold: XrPressThreshold {
+                press: 0.6,
+                release: 0.4,
+            },
+            ..default()
+        };
+        let states: Vec<(bool, bool, bool)> = [0.5, 0.7, 0.5, 0.65, 0.45, 0.3, 0.5, 0.55]
+            .into_iter()
+            .map(|value| {
+                state.update(value);
+                (state.is_pressed, state.pressed, state.released)
+            })
+            .collect();
+        assert_eq!(
+            states,
+            [
+                (false, false, false),
+                // past press
+                (true, true, false),
+                // between the two it stays as it was
+                (true, false, false),
+                (true, false, false),
+                (true, false, false),
+                // below release
+                (false, false, true),
+                (false, false, false),
+                (false, false, false),
+            ]
+        );
+    }
 }
@@ -0,0 +1,178 @@
+use bevy::{
+    ecs::intern::{Interned, Interner},
+    prelude::*,
+};
+
+use super::{
+    actions::{Hand, XrAction, XrActionState, XrInput},
+    contexts::XrInputContexts,
+};
+
+static ACTION_NAMES: Interner<str> = Interner::new();
+
+/// A bool or float action in [`ButtonInput<XrButton>`], `hand` is `None` for the combined state.
+/// Floats count as pressed past their press threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct XrButton {
+    name: Interned<str>,
+    pub hand: Option<Hand>,
+}
+
+impl XrButton {
+    pub fn new(action: &XrAction, hand: Option<Hand>) -> Self {
+        Self {
+            name: ACTION_NAMES.intern(action.name.as_str()),
+            hand,
+        }
+    }
+
+    /// Name of the action
+    pub fn name(&self) -> &'static str {
+        self.name.0
+    }
+}
+
+/// Which value of an action an [`XrAxis`] reads
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum XrAxisComponent {
+    /// The value of a float action
+    Value,
+    /// The x of a Vec2 action
+    X,
+    /// The y of a Vec2 action
+    Y,
+}
+
+/// A float action or one half of a Vec2 action in [`Axis<XrAxis>`], `hand` is `None` for the
+/// combined state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct XrAxis {
+    name: Interned<str>,
+    pub hand: Option<Hand>,
+    pub component: XrAxisComponent,
+}
+
+impl XrAxis {
+    pub fn new(action: &XrAction, hand: Option<Hand>, component: XrAxisComponent) -> Self {
+        Self {
+            name: ACTION_NAMES.intern(action.name.as_str()),
+            hand,
+            component,
+        }
+    }
+
+    pub fn value(action: &XrAction, hand: Option<Hand>) -> Self {
+        Self::new(action, hand, XrAxisComponent::Value)
+    }
+
+    pub fn x(action: &XrAction, hand: Option<Hand>) -> Self {
+        Self::new(action, hand, XrAxisComponent::X)
+    }
+
+    pub fn y(action: &XrAction, hand: Option<Hand>) -> Self {
+        Self::new(action, hand, XrAxisComponent::Y)
+    }
+
+    /// Name of the action
+    pub fn name(&self) -> &'static str {
+        self.name.0
+    }
+}
+
+/// Copies [`XrInput`] into [`ButtonInput<XrButton>`] and [`Axis<XrAxis>`] right after it's
+/// updated, so `input_just_pressed(XrButton::new(..))` and friends work for controllers. Actions
+/// an [`XrInputContexts`] consumed read as released.
+pub fn update_button_input(
+    inputs: Option<Res<XrInput>>,
+    contexts: Res<XrInputContexts>,
+    mut buttons: ResMut<ButtonInput<XrButton>>,
+    mut axes: ResMut<Axis<XrAxis>>,
+) {
+    // only the edges from this frame count, like bevy's keyboard input
+    buttons.bypass_change_detection().clear();
+    let Some(inputs) = inputs else {
+        // the session ended or the actions are being rebuilt, nothing is held anymore
+        if buttons.get_pressed().next().is_some() {
+            buttons.release_all();
+        }
+        let devices: Vec<XrAxis> = axes.all_axes().copied().collect();
+        for axis in devices {
+            axes.remove(axis);
+        }
+        return;
+    };
+    let states = inputs
+        .state
+        .iter()
+        .map(|(action, state)| (action, None, state))
+        .chain(
+            inputs
+                .hands
+                .iter()
+                .map(|((action, hand), state)| (action, Some(*hand), state)),
+        );
+    for (action, hand, state) in states {
+        if contexts.is_consumed(action, hand) {
+            match state {
+                XrActionState::Float(_) => {
+                    axes.set(XrAxis::value(action, hand), 0.0);
+                    buttons.release(XrButton::new(action, hand));
+                }
+                XrActionState::Bool(_) => buttons.release(XrButton::new(action, hand)),
+                XrActionState::Vec2(_) => {
+                    axes.set(XrAxis::x(action, hand), 0.0);
+                    axes.set(XrAxis::y(action, hand), 0.0);
+                }
+                XrActionState::Pose(_) => {}
+            }
+            continue;
+        }
+        let pressed = match state {
+            XrActionState::Float(x) => {
+                axes.set(XrAxis::value(action, hand), x.cur_val);
+                Some(x.is_pressed)
+            }
+            XrActionState::Bool(x) => Some(x.cur_val),
+            XrActionState::Vec2(x) => {
+                axes.set(XrAxis::x(action, hand), x.cur_val.x);
+                axes.set(XrAxis::y(action, hand), x.cur_val.y);
+                None
+            }
+            XrActionState::Pose(_) => None,
+        };
+        match pressed {
+            Some(true) => buttons.press(XrButton::new(action, hand)),
+            Some(false) => buttons.release(XrButton::new(action, hand)),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::input::{config::DefaultActions, typed::XrTypedAction};
+
+    #[test]
+    fn everything_is_released_without_input() {
+        let squeeze = DefaultActions::Squeeze.action();
+        let mut world = World::new();
+        world.init_resource::<XrInputContexts>();
+        let mut buttons = ButtonInput::<XrButton>::default();
+        buttons.press(XrButton::new(&squeeze, Some(Hand::Left)));
+        world.insert_resource(buttons);
+        let mut axes = Axis::<XrAxis>::default();
+        axes.set(XrAxis::value(&squeeze, Some(Hand::Left)), 1.0);
+        world.insert_resource(axes);
+
+        world.run_system_once(update_button_input).unwrap();
+
+        let buttons = world.resource::<ButtonInput<XrButton>>();
+        assert!(!buttons.pressed(XrButton::new(&squeeze, Some(Hand::Left))));
+        assert!(buttons.just_released(XrButton::new(&squeeze, Some(Hand::Left))));
+        let axes = world.resource::<Axis<XrAxis>>();
+        assert_eq!(axes.get(XrAxis::value(&squeeze, Some(Hand::Left))), None);
+    }
+}
@@ -0,0 +1,547 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    actions::{Hand, XrAction, XrActionType},
+    fallback::XrFallbackBinding,
+    haptics::{XrHapticEasing, XrHapticKeyframe, XrHapticPattern},
+    joystick::XrJoystickSettings,
+    profiles::{preset_bindings, XrPresetInput},
+};
+
+/// An interaction profile and the input paths an action is suggested on for it
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct XrBinding {
+    pub interaction_profile: String,
+    pub binding: Vec<String>,
+}
+
+/// A single action in the set along with its suggested bindings for every profile
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct XrActionConfig {
+    pub name: String,
+    pub pretty_name: String,
+    pub action_type: XrActionType,
+    /// Hands the action can be queried for separately, the bindings still need the full paths
+    #[serde(default)]
+    pub subaction_paths: Vec<Hand>,
+    #[serde(default)]
+    pub bindings: Vec<XrBinding>,
+    #[serde(default)]
+    pub timing: XrActionTiming,
+    #[serde(default)]
+    pub threshold: XrPressThreshold,
+    /// Only used by Vec2 actions
+    #[serde(default)]
+    pub joystick: XrJoystickSettings,
+    /// Name of the set from [`Config::sets`] the action goes in, the main set when `None`
+    #[serde(default)]
+    pub set: Option<String>,
+    /// Keyboard, mouse and gamepad inputs that drive the action without a headset, see
+    /// [`XrFallbackPlugin`](super::fallback::XrFallbackPlugin)
+    #[serde(default)]
+    pub fallback: Vec<XrFallbackBinding>,
+}
+
+/// When a float action counts as pressed, keeping `release` below `press` stops noisy sensors
+/// from flickering between the two
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct XrPressThreshold {
+    /// Pressed once the value goes above this
+    pub press: f32,
+    /// Released once the value is at or below this
+    pub release: f32,
+}
+
+impl Default for XrPressThreshold {
+    fn default() -> Self {
+        Self {
+            press: 0.0,
+            release: 0.0,
+        }
+    }
+}
+
+/// Thresholds for the long press and double tap detection of float and bool actions
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct XrActionTiming {
+    /// Seconds the action has to be held for a long press
+    pub long_press: f32,
+    /// Most seconds between two presses for the second one to be a double tap
+    pub double_tap: f32,
+}
+
+impl Default for XrActionTiming {
+    fn default() -> Self {
+        Self {
+            long_press: 0.5,
+            double_tap: 0.3,
+        }
+    }
+}
+
+impl XrActionConfig {
+    pub fn action(&self) -> XrAction {
+        XrAction {
+            name: self.name.clone(),
+            pretty_name: self.pretty_name.clone(),
+            action_type: self.action_type.clone(),
+        }
+    }
+}
+
+/// The action set used to build [`XrActions`](super::actions::XrActions).
+/// Insert your own before adding the plugin (for example with [`Config::load_or_default`])
+/// otherwise [`Config::default`] is used. It can also be loaded as an asset from `*.actions.toml`
+/// files, see [`XrActionConfigHandle`].
+///
+/// The sets, actions and bindings are locked in once the session starts, changing them after that
+/// only takes effect on the next launch. The other settings apply right away.
+///
+/// ```toml
+/// set_name = "mine"
+/// set_pretty_name = "My set"
+///
+/// [[actions]]
+/// name = "squeeze"
+/// pretty_name = "Hand Squeeze"
+/// action_type = "Float"
+/// subaction_paths = ["/user/hand/left", "/user/hand/right"]
+///
+/// [actions.timing]
+/// long_press = 0.8
+///
+/// [actions.threshold]
+/// press = 0.6
+/// release = 0.4
+///
+/// [[actions.bindings]]
+/// interaction_profile = "/interaction_profiles/oculus/touch_controller"
+/// binding = ["/user/hand/left/input/squeeze/value", "/user/hand/right/input/squeeze/value"]
+///
+/// [[actions.fallback]]
+/// input = { GamepadButton = "RightTrigger2" }
+/// hand = "/user/hand/right"
+///
+/// [[sets]]
+/// name = "menu"
+/// pretty_name = "Menu"
+/// priority = 1
+///
+/// [[actions]]
+/// name = "menu_select"
+/// pretty_name = "Menu Select"
+/// action_type = "Bool"
+/// set = "menu"
+/// ```
+#[derive(Deserialize, Serialize, Clone, Resource, Asset, TypePath, Debug)]
+pub struct Config {
+    pub set_name: String,
+    pub set_pretty_name: String,
+    /// Priority of the main set, see [`XrActionSetConfig::priority`]
+    #[serde(default)]
+    pub priority: u32,
+    /// Sets besides the main one, they can be switched on and off with
+    /// [`XrActiveActionSets`](super::action_sets::XrActiveActionSets)
+    #[serde(default)]
+    pub sets: Vec<XrActionSetConfig>,
+    pub actions: Vec<XrActionConfig>,
+    #[serde(default)]
+    pub haptic_patterns: Vec<XrHapticPattern>,
+}
+
+/// An extra action set, see [`Config::sets`]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct XrActionSetConfig {
+    pub name: String,
+    pub pretty_name: String,
+    /// When two active sets bind the same input the one with the higher priority gets it and the
+    /// other one's action reads as released
+    #[serde(default)]
+    pub priority: u32,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Parse {
+        path: Option<PathBuf>,
+        source: toml::de::Error,
+    },
+    Serialize(toml::ser::Error),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(
+                    f,
+                    "couldn't read action config {}: {source}",
+                    path.display()
+                )
+            }
+            ConfigError::Parse {
+                path: Some(path),
+                source,
+            } => write!(f, "malformed action config {}: {source}", path.display()),
+            ConfigError::Parse { path: None, source } => {
+                write!(f, "malformed action config: {source}")
+            }
+            ConfigError::Serialize(e) => write!(f, "couldn't serialize config: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source),
+            ConfigError::Serialize(e) => Some(e),
+        }
+    }
+}
+
+impl Config {
+    pub fn new(set_name: &str, set_pretty_name: &str, actions: Vec<XrActionConfig>) -> Self {
+        Self {
+            set_name: set_name.to_string(),
+            set_pretty_name: set_pretty_name.to_string(),
+            priority: 0,
+            sets: Vec::new(),
+            actions,
+            haptic_patterns: Vec::new(),
+        }
+    }
+
+    pub fn from_toml_str(string: &str) -> Result<Self, ConfigError> {
+        toml::from_str(string).map_err(|source| ConfigError::Parse { path: None, source })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let string = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&string).map_err(|source| ConfigError::Parse {
+            path: Some(path.to_path_buf()),
+            source,
+        })
+    }
+
+    /// Same as [`Config::load`] but logs the error and falls back to [`Config::default`]
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        match Self::load(path) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("{e}, falling back to the default action config");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+
+    /// If both configs create the same sets, actions and bindings. The rest can change while the
+    /// app runs without creating the actions again.
+    pub(crate) fn same_actions(&self, other: &Config) -> bool {
+        self.set_name == other.set_name
+            && self.set_pretty_name == other.set_pretty_name
+            && self.priority == other.priority
+            && self.sets == other.sets
+            && self.actions.len() == other.actions.len()
+            && self.actions.iter().zip(other.actions.iter()).all(|(a, b)| {
+                a.name == b.name
+                    && a.pretty_name == b.pretty_name
+                    && a.action_type == b.action_type
+                    && a.subaction_paths == b.subaction_paths
+                    && a.bindings == b.bindings
+                    && a.set == b.set
+            })
+    }
+}
+
+const HANDS: [&str; 2] = ["/user/hand/left", "/user/hand/right"];
+
+crate::xr_actions! {
+    /// Actions in [`Config::default`]
+    pub DefaultActions {
+        Pose: openxr::Posef = "pose", "Hand Grip Pose",
+            subactions: [Hand::Left, Hand::Right],
+            bindings: preset_bindings(XrPresetInput::GripPose, &HANDS);
+        Joystick: Vec2 = "joystick", "Hand JoyStick",
+            subactions: [Hand::Left, Hand::Right],
+            bindings: preset_bindings(XrPresetInput::Joystick, &HANDS);
+        Squeeze: f32 = "squeeze", "Hand Squeeze",
+            subactions: [Hand::Left, Hand::Right],
+            bindings: preset_bindings(XrPresetInput::Squeeze, &HANDS);
+        Haptic: openxr::Haptic = "haptic", "Hand Haptic",
+            subactions: [Hand::Left, Hand::Right],
+            bindings: preset_bindings(XrPresetInput::Haptic, &HANDS);
+    }
+}
+
+/// Suggests bindings for every profile in [`PROFILE_PRESETS`](super::profiles::PROFILE_PRESETS)
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            actions: DefaultActions::action_configs(),
+            haptic_patterns: vec![
+                XrHapticPattern {
+                    name: "grab".to_string(),
+                    keyframes: vec![
+                        XrHapticKeyframe {
+                            time: 0.0,
+                            amplitude: 0.6,
+                            frequency: 0.0,
+                        },
+                        XrHapticKeyframe {
+                            time: 0.06,
+                            amplitude: 0.0,
+                            frequency: 0.0,
+                        },
+                    ],
+                    easing: XrHapticEasing::EaseOut,
+                    looping: false,
+                    priority: 1,
+                },
+                XrHapticPattern {
+                    name: "tick".to_string(),
+                    keyframes: vec![
+                        XrHapticKeyframe {
+                            time: 0.0,
+                            amplitude: 0.3,
+                            frequency: 0.0,
+                        },
+                        XrHapticKeyframe {
+                            time: 0.015,
+                            amplitude: 0.0,
+                            frequency: 0.0,
+                        },
+                    ],
+                    easing: XrHapticEasing::Step,
+                    looping: false,
+                    priority: 0,
+                },
+                XrHapticPattern {
+                    name: "impact".to_string(),
+                    keyframes: vec![
+                        XrHapticKeyframe {
+                            time: 0.0,
+                            amplitude: 1.0,
+                            frequency: 0.0,
+                        },
+                        XrHapticKeyframe {
+                            time: 0.25,
+                            amplitude: 0.0,
+                            frequency: 0.0,
+                        },
+                    ],
+                    easing: XrHapticEasing::EaseIn,
+                    looping: false,
+                    priority: 2,
+                },
+            ],
+            set_name: "mine".to_string(),
+            set_pretty_name: "My set".to_string(),
+            priority: 0,
+            sets: Vec::new(),
+        }
+    }
+}
+
+/// Keeps the [`Config`] resource in sync with a loaded `*.actions.toml` asset.
+/// With bevy's `file_watcher` feature edits to the file are picked up while the app runs.
+///
+/// The actions and bindings in the file are only used if it finishes loading before the session
+/// starts, otherwise they wait for the next launch. Load it in a `Startup` system, or read it with
+/// [`Config::load`] before adding the plugin when the bindings have to apply on the first launch.
+#[derive(Resource)]
+pub struct XrActionConfigHandle(pub Handle<Config>);
+
+#[derive(Default)]
+pub struct ConfigLoader;
+
+impl AssetLoader for ConfigLoader {
+    type Asset = Config;
+    type Settings = ();
+    type Error = ConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let path = load_context.path().to_path_buf();
+        let mut bytes = Vec::new();
+        if let Err(source) = reader.read_to_end(&mut bytes).await {
+            return Err(ConfigError::Io { path, source });
+        }
+        let string = match String::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(e) => {
+                return Err(ConfigError::Io {
+                    path,
+                    source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+                })
+            }
+        };
+        toml::from_str(&string).map_err(|source| ConfigError::Parse {
+            path: Some(path),
+            source,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["actions.toml"]
+    }
+}
+
+pub fn apply_config_asset(
+    handle: Option<Res<XrActionConfigHandle>>,
+    assets: Res<Assets<Config>>,
+    mut events: EventReader<AssetEvent<Config>>,
+    mut config: ResMut<Config>,
+) {
+    let Some(handle) = handle else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }
+                if *id == handle.0.id() =>
+            {
+                if let Some(new_config) = assets.get(*id) {
+                    *config = new_config.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::fallback::XrFallbackInput;
+
+    #[test]
+    fn parses_the_documented_example() {
+        let config = Config::from_toml_str(
+            r#"
+            set_name = "mine"
+            set_pretty_name = "My set"
+
+            [[actions]]
+            name = "squeeze"
+            pretty_name = "Hand Squeeze"
+            action_type = "Float"
+            subaction_paths = ["/user/hand/left", "/user/hand/right"]
+
+            [actions.timing]
+            long_press = 0.8
+
+            [actions.threshold]
+            press = 0.6
+            release = 0.4
+
+            [[actions.bindings]]
+            interaction_profile = "/interaction_profiles/oculus/touch_controller"
+            binding = ["/user/hand/left/input/squeeze/value", "/user/hand/right/input/squeeze/value"]
+
+            [[actions.fallback]]
+            input = { GamepadButton = "RightTrigger2" }
+            hand = "/user/hand/right"
+
+            [[sets]]
+            name = "menu"
+            pretty_name = "Menu"
+            priority = 1
+
+            [[actions]]
+            name = "menu_select"
+            pretty_name = "Menu Select"
+            action_type = "Bool"
+            set = "menu"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.set_name, "mine");
+        assert_eq!(config.priority, 0);
+        assert_eq!(config.sets.len(), 1);
+        assert_eq!(config.sets[0].priority, 1);
+        assert_eq!(config.actions.len(), 2);
+
+        let squeeze = &config.actions[0];
+        assert_eq!(squeeze.action_type, XrActionType::Float);
+        assert_eq!(squeeze.subaction_paths, vec![Hand::Left, Hand::Right]);
+        // left out keys keep their defaults
+        assert_eq!(
+            squeeze.timing,
+            XrActionTiming {
+                long_press: 0.8,
+                ..default()
+            }
+        );
+        assert_eq!(squeeze.threshold.press, 0.6);
+        assert_eq!(squeeze.bindings[0].binding.len(), 2);
+        assert_eq!(
+            squeeze.fallback,
+            vec![XrFallbackBinding::on(
+                XrFallbackInput::GamepadButton(GamepadButton::RightTrigger2),
+                Hand::Right
+            )]
+        );
+        assert_eq!(squeeze.set, None);
+
+        let select = &config.actions[1];
+        assert_eq!(select.action_type, XrActionType::Bool);
+        assert_eq!(select.set.as_deref(), Some("menu"));
+        assert!(select.bindings.is_empty());
+    }
+
+    #[test]
+    fn malformed_toml_is_a_parse_error() {
+        assert!(matches!(
+            Config::from_toml_str("set_name = "),
+            Err(ConfigError::Parse { path: None, .. })
+        ));
+        // well formed but missing the actions
+        assert!(matches!(
+            Config::from_toml_str("set_name = \"mine\"\nset_pretty_name = \"My set\""),
+            Err(ConfigError::Parse { path: None, .. })
+        ));
+
+        let path = std::env::temp_dir().join("xr_utils_malformed.actions.toml");
+        std::fs::write(&path, "[[actions]").unwrap();
+        let loaded = Config::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            loaded,
+            Err(ConfigError::Parse { path: Some(p), .. }) if p == path
+        ));
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let config = Config::default();
+        let parsed = Config::from_toml_str(&config.to_toml_string().unwrap()).unwrap();
+        assert!(parsed.same_actions(&config));
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::actions::{Hand, XrAction};
+
+/// Systems of contexts that consume input go in here, it runs in `PreUpdate` once the frame's
+/// [`XrInput`](super::actions::XrInput) is final and before it's copied into
+/// [`ButtonInput<XrButton>`](super::button_input::XrButton) and
+/// [`XrFixedInput`](super::fixed::XrFixedInput), which see consumed actions as released
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub struct XrInputContextSet;
+
+/// A stack of named input contexts on top of [`XrInput`](super::actions::XrInput), like a focused
+/// menu over gameplay. A context that handled an action consumes it so everything below ignores
+/// it for the rest of the frame, consumed actions are forgotten at the end of every frame.
+///
+/// When several contexts consume the same action the one highest on the stack gets it, whatever
+/// order their systems ran in. Put the systems that consume in [`XrInputContextSet`] and act on
+/// the input once it has run, by checking [`consumed_by`](Self::consumed_by).
+#[derive(Resource, Default, Debug, Clone)]
+pub struct XrInputContexts {
+    /// Bottom to top
+    stack: Vec<String>,
+    /// Which context consumed what, `None` is every hand
+    consumed: HashMap<(XrAction, Option<Hand>), String>,
+}
+
+impl XrInputContexts {
+    /// Puts `context` on top, moving it there if it's already on the stack
+    pub fn push(&mut self, context: impl Into<String>) {
+        let context = context.into();
+        self.stack.retain(|c| *c != context);
+        self.stack.push(context);
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.stack.pop()
+    }
+
+    /// Takes `context` off the stack wherever it is
+    pub fn remove(&mut self, context: &str) {
+        self.stack.retain(|c| c != context);
+    }
+
+    pub fn top(&self) -> Option<&str> {
+        self.stack.last().map(String::as_str)
+    }
+
+    /// Every context from the bottom up
+    pub fn stack(&self) -> &[String] {
+        &self.stack
+    }
+
+    pub fn is_pushed(&self, context: &str) -> bool {
+        self.stack.iter().any(|c| c == context)
+    }
+
+    fn depth(&self, context: &str) -> Option<usize> {
+        self.stack.iter().position(|c| c == context)
+    }
+
+    /// Marks `action` as handled by `context` for this frame, `hand` `None` consumes it on every
+    /// hand. Returns false if `context` isn't on the stack or a context above it consumed the
+    /// action, a context above it can still take it later in [`XrInputContextSet`].
+    pub fn consume(&mut self, context: &str, action: &XrAction, hand: Option<Hand>) -> bool {
+        let Some(depth) = self.depth(context) else {
+            warn!(
+                "{context} isn't on the input context stack, it can't consume {}",
+                action.name
+            );
+            return false;
+        };
+        if self
+            .overlapping(action, hand)
+            .any(|by| self.depth(by).is_some_and(|d| d > depth))
+        {
+            return false;
+        }
+        // a lower context's claim on the exact same input is overridden either way
+        self.consumed
+            .insert((action.clone(), hand), context.to_string());
+        true
+    }
+
+    fn overlapping<'a>(
+        &'a self,
+        action: &'a XrAction,
+        hand: Option<Hand>,
+    ) -> impl Iterator<Item = &'a str> {
+        self.consumed
+            .iter()
+            .filter(move |((a, h), _)| a == action && (h.is_none() || hand.is_none() || *h == hand))
+            .map(|(_, context)| context.as_str())
+    }
+
+    pub fn is_consumed(&self, action: &XrAction, hand: Option<Hand>) -> bool {
+        self.consumed_by(action, hand).is_some()
+    }
+
+    /// The highest context on the stack that consumed `action` on `hand` this frame, `None` asks
+    /// about any hand
+    pub fn consumed_by<'a>(&'a self, action: &'a XrAction, hand: Option<Hand>) -> Option<&'a str> {
+        self.overlapping(action, hand)
+            .filter_map(|by| Some((self.depth(by)?, by)))
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, by)| by)
+    }
+
+    /// Every claim made this frame and by which context, including the ones a higher context
+    /// overrode, for debugging
+    pub fn consumed(&self) -> impl Iterator<Item = (&XrAction, Option<Hand>, &str)> {
+        self.consumed
+            .iter()
+            .map(|((action, hand), context)| (action, *hand, context.as_str()))
+    }
+
+    pub(crate) fn end_frame(&mut self) {
+        self.consumed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{config::DefaultActions, typed::XrTypedAction};
+
+    fn contexts() -> XrInputContexts {
+        let mut contexts = XrInputContexts::default();
+        contexts.push("gameplay");
+        contexts.push("menu");
+        contexts
+    }
+
+    #[test]
+    fn higher_context_wins_whatever_the_order() {
+        let squeeze = DefaultActions::Squeeze.action();
+        for lower_first in [true, false] {
+            let mut contexts = contexts();
+            if lower_first {
+                assert!(contexts.consume("gameplay", &squeeze, None));
+                assert!(contexts.consume("menu", &squeeze, None));
+            } else {
+                assert!(contexts.consume("menu", &squeeze, None));
+                assert!(!contexts.consume("gameplay", &squeeze, None));
+            }
+            assert_eq!(contexts.consumed_by(&squeeze, None), Some("menu"));
+            assert_eq!(
+                contexts.consumed_by(&squeeze, Some(Hand::Left)),
+                Some("menu")
+            );
+        }
+    }
+
+    #[test]
+    fn hands_are_claimed_separately() {
+        let squeeze = DefaultActions::Squeeze.action();
+        let mut contexts = contexts();
+        assert!(contexts.consume("gameplay", &squeeze, None));
+        assert!(contexts.consume("menu", &squeeze, Some(Hand::Left)));
+        assert!(!contexts.consume("gameplay", &squeeze, Some(Hand::Left)));
+        assert_eq!(
+            contexts.consumed_by(&squeeze, Some(Hand::Left)),
+            Some("menu")
+        );
+        assert_eq!(
+            contexts.consumed_by(&squeeze, Some(Hand::Right)),
+            Some("gameplay")
+        );
+    }
+
+    #[test]
+    fn only_pushed_contexts_consume() {
+        let squeeze = DefaultActions::Squeeze.action();
+        let mut contexts = contexts();
+        assert!(!contexts.consume("pause", &squeeze, None));
+        assert!(contexts.consume("menu", &squeeze, None));
+        contexts.remove("menu");
+        assert!(!contexts.is_consumed(&squeeze, None));
+        contexts.end_frame();
+        assert_eq!(contexts.consumed().count(), 0);
+    }
+}
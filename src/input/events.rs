@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use super::actions::{Hand, XrAction, XrActionState, XrInput};
+
+/// Sent when an action's value changes, `hand` is `None` for the combined state. `state` holds
+/// the new value in `cur_val` and the last frame's in `previous_val`.
+#[derive(Event, Clone, Debug)]
+pub enum XrActionEvent {
+    /// Went from rest to active, a bool turning on, a float passing its press threshold or a vec2
+    /// starting to point in a [`XrStickDirection`](super::joystick::XrStickDirection)
+    Pressed {
+        action: XrAction,
+        hand: Option<Hand>,
+        state: XrActionState,
+    },
+    /// Went back to rest
+    Released {
+        action: XrAction,
+        hand: Option<Hand>,
+        state: XrActionState,
+    },
+    /// Sent for every change, alongside `Pressed` and `Released`
+    Changed {
+        action: XrAction,
+        hand: Option<Hand>,
+        state: XrActionState,
+    },
+}
+
+impl XrActionEvent {
+    pub fn action(&self) -> &XrAction {
+        match self {
+            XrActionEvent::Pressed { action, .. }
+            | XrActionEvent::Released { action, .. }
+            | XrActionEvent::Changed { action, .. } => action,
+        }
+    }
+
+    pub fn hand(&self) -> Option<Hand> {
+        match self {
+            XrActionEvent::Pressed { hand, .. }
+            | XrActionEvent::Released { hand, .. }
+            | XrActionEvent::Changed { hand, .. } => *hand,
+        }
+    }
+
+    pub fn state(&self) -> &XrActionState {
+        match self {
+            XrActionEvent::Pressed { state, .. }
+            | XrActionEvent::Released { state, .. }
+            | XrActionEvent::Changed { state, .. } => state,
+        }
+    }
+}
+
+/// (pressed, released, changed), also what [`XrFixedInput`](super::fixed::XrFixedInput) counts
+/// as pressed. A stick is pressed while it points in a direction so the thresholds apply.
+pub(crate) fn transition(state: &XrActionState) -> (bool, bool, bool) {
+    match state {
+        XrActionState::Float(x) => (x.pressed, x.released, x.previous_val != x.cur_val),
+        XrActionState::Bool(x) => (x.pressed, x.released, x.previous_val != x.cur_val),
+        XrActionState::Vec2(x) => (
+            x.previous_direction.is_none() && x.direction.is_some(),
+            x.previous_direction.is_some() && x.direction.is_none(),
+            x.previous_val != x.cur_val,
+        ),
+        XrActionState::Pose(_) => (false, false, false),
+    }
+}
+
+/// Runs right after [`update_inputs`](super::actions::update_inputs) and the mock backends, so
+/// `cur_val` is this frame's value and `previous_val` still last frame's
+pub fn send_action_events(inputs: Option<Res<XrInput>>, mut events: EventWriter<XrActionEvent>) {
+    let Some(inputs) = inputs else {
+        return;
+    };
+    let states = inputs
+        .state
+        .iter()
+        .map(|(action, state)| (action, None, state))
+        .chain(
+            inputs
+                .hands
+                .iter()
+                .map(|((action, hand), state)| (action, Some(*hand), state)),
+        );
+    for (action, hand, state) in states {
+        let (pressed, released, changed) = transition(state);
+        if pressed {
+            events.send(XrActionEvent::Pressed {
+                action: action.clone(),
+                hand,
+                state: *state,
+            });
+        }
+        if released {
+            events.send(XrActionEvent::Released {
+                action: action.clone(),
+                hand,
+                state: *state,
+            });
+        }
+        if changed {
+            events.send(XrActionEvent::Changed {
+                action: action.clone(),
+                hand,
+                state: *state,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{mock_app, XrMockFrame, XrMockInput, XrMockScript, XrMockValue},
+        prelude::{DefaultActions, XrTypedAction},
+    };
+
+    /// The combined events for `action` each frame, `values` are played back one per frame
+    fn events(action: XrAction, values: &[Option<XrMockValue>]) -> Vec<Vec<&'static str>> {
+        let frames = values
+            .iter()
+            .map(|value| XrMockFrame {
+                inputs: value
+                    .map(|value| XrMockInput {
+                        action: action.clone(),
+                        hand: None,
+                        value,
+                    })
+                    .into_iter()
+                    .collect(),
+                ..default()
+            })
+            .collect();
+        let mut app = mock_app(XrMockScript::new(frames));
+        values
+            .iter()
+            .map(|_| {
+                app.update();
+                app.world_mut()
+                    .resource_mut::<Events<XrActionEvent>>()
+                    .drain()
+                    .filter(|event| *event.action() == action && event.hand().is_none())
+                    .map(|event| match event {
+                        XrActionEvent::Pressed { .. } => "pressed",
+                        XrActionEvent::Released { .. } => "released",
+                        XrActionEvent::Changed { .. } => "changed",
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn float_events_in_order() {
+        let float = |value| Some(XrMockValue::Float(value));
+        assert_eq!(
+            events(
+                DefaultActions::Squeeze.action(),
+                &[None, float(0.5), float(1.0), None]
+            ),
+            [
+                vec![],
+                vec!["pressed", "changed"],
+                vec!["changed"],
+                vec!["released", "changed"],
+            ]
+        );
+    }
+
+    #[test]
+    fn stick_presses_past_the_direction_threshold() {
+        let stick = |y| Some(XrMockValue::Vec2(Vec2::new(0.0, y)));
+        assert_eq!(
+            events(
+                DefaultActions::Joystick.action(),
+                &[None, stick(0.3), stick(0.9), None]
+            ),
+            [
+                vec![],
+                vec!["changed"],
+                vec!["pressed", "changed"],
+                vec!["released", "changed"],
+            ]
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use bevy_mod_xr::session::session_running;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    actions::{update_pose_world, Hand, XrActionState, XrInput},
+    config::Config,
+    XrSystemSet,
+};
+
+/// A keyboard, mouse or gamepad input standing in for a controller. Buttons read as 0.0 or 1.0,
+/// analog triggers and axes as their value and the stick variants as a Vec2.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum XrFallbackInput {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Analog on triggers
+    GamepadButton(GamepadButton),
+    GamepadAxis(GamepadAxis),
+    /// Four keys as a stick, for Vec2 actions
+    Keys {
+        up: KeyCode,
+        down: KeyCode,
+        left: KeyCode,
+        right: KeyCode,
+    },
+    /// Two gamepad axes as a stick, for Vec2 actions
+    GamepadStick {
+        x: GamepadAxis,
+        y: GamepadAxis,
+    },
+}
+
+/// A non-XR input for an action, `hand` picks which hand's state it drives. The combined state
+/// is driven by all of them.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct XrFallbackBinding {
+    pub input: XrFallbackInput,
+    #[serde(default)]
+    pub hand: Option<Hand>,
+}
+
+impl XrFallbackBinding {
+    pub fn new(input: XrFallbackInput) -> Self {
+        Self { input, hand: None }
+    }
+
+    pub fn on(input: XrFallbackInput, hand: Hand) -> Self {
+        Self {
+            input,
+            hand: Some(hand),
+        }
+    }
+}
+
+enum FallbackValue {
+    Scalar(f32),
+    Stick(Vec2),
+}
+
+impl FallbackValue {
+    fn scalar(&self) -> f32 {
+        match self {
+            FallbackValue::Scalar(x) => *x,
+            FallbackValue::Stick(x) => x.length(),
+        }
+    }
+}
+
+impl XrFallbackInput {
+    fn read(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Query<&Gamepad>,
+    ) -> FallbackValue {
+        let key = |key: KeyCode| keys.pressed(key) as i32 as f32;
+        let axis = |axis: GamepadAxis| {
+            gamepads
+                .iter()
+                .filter_map(|gamepad| gamepad.get(axis))
+                .fold(0.0f32, |a, b| if b.abs() > a.abs() { b } else { a })
+        };
+        match *self {
+            XrFallbackInput::Key(k) => FallbackValue::Scalar(key(k)),
+            XrFallbackInput::Mouse(button) => {
+                FallbackValue::Scalar(mouse.pressed(button) as i32 as f32)
+            }
+            XrFallbackInput::GamepadButton(button) => FallbackValue::Scalar(
+                gamepads
+                    .iter()
+                    .map(|gamepad| {
+                        let digital = gamepad.pressed(button) as i32 as f32;
+                        gamepad.get(button).unwrap_or(0.0).max(digital)
+                    })
+                    .fold(0.0, f32::max),
+            ),
+            XrFallbackInput::GamepadAxis(a) => FallbackValue::Scalar(axis(a)),
+            XrFallbackInput::Keys {
+                up,
+                down,
+                left,
+                right,
+            } => FallbackValue::Stick(
+                Vec2::new(key(right) - key(left), key(up) - key(down)).normalize_or_zero(),
+            ),
+            XrFallbackInput::GamepadStick { x, y } => FallbackValue::Stick(
+                gamepads
+                    .iter()
+                    .map(|gamepad| {
+                        Vec2::new(gamepad.get(x).unwrap_or(0.0), gamepad.get(y).unwrap_or(0.0))
+                    })
+                    .fold(Vec2::ZERO, |a, b| {
+                        if b.length_squared() > a.length_squared() {
+                            b
+                        } else {
+                            a
+                        }
+                    }),
+            ),
+        }
+    }
+}
+
+/// The [`XrInput`] was made by the fallback and has to go once a session runs
+#[derive(Resource)]
+struct XrFallbackInputOwned;
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub struct XrFallbackSet;
+
+/// Fills [`XrInput`] from the `fallback` bindings of every action in the [`Config`] while no
+/// OpenXR session is running, so the same gameplay code works on a flat screen. Don't combine it
+/// with the [`XrSimulatorPlugin`](crate::prelude::XrSimulatorPlugin), both write the same input.
+pub struct XrFallbackPlugin;
+
+impl Plugin for XrFallbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(
+            PreUpdate,
+            XrFallbackSet
+                .before(update_pose_world)
+                .run_if(not(session_running)),
+        );
+        app.add_systems(
+            PreUpdate,
+            (setup_fallback, update_fallback_input)
+                .chain()
+                .in_set(XrFallbackSet),
+        );
+        app.add_systems(
+            PreUpdate,
+            release_fallback_input
+                .before(XrSystemSet::Init)
+                .run_if(session_running)
+                .run_if(resource_exists::<XrFallbackInputOwned>),
+        );
+    }
+}
+
+fn setup_fallback(config: Res<Config>, inputs: Option<Res<XrInput>>, mut cmds: Commands) {
+    if inputs.is_none() {
+        cmds.insert_resource(XrInput::from_config(&config));
+        cmds.insert_resource(XrFallbackInputOwned);
+    }
+}
+
+/// Makes room for the [`XrInput`] built from the session's actions
+fn release_fallback_input(mut cmds: Commands) {
+    cmds.remove_resource::<XrInput>();
+    cmds.remove_resource::<XrFallbackInputOwned>();
+}
+
+fn update_fallback_input(
+    config: Res<Config>,
+    inputs: Option<ResMut<XrInput>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+) {
+    // inserted by setup_fallback, shows up next frame
+    let Some(mut inputs) = inputs else {
+        return;
+    };
+    for action_config in config.actions.iter() {
+        if action_config.fallback.is_empty() {
+            continue;
+        }
+        let action = action_config.action();
+        let values: Vec<(Option<Hand>, FallbackValue)> = action_config
+            .fallback
+            .iter()
+            .map(|binding| (binding.hand, binding.input.read(&keys, &mouse, &gamepads)))
+            .collect();
+        let targets =
+            std::iter::once(None).chain(action_config.subaction_paths.iter().copied().map(Some));
+        for target in targets {
+            let Some(state) = inputs.get_mut(&action, target) else {
+                continue;
+            };
+            // the combined state takes every binding, a hand only its own
+            let values = values
+                .iter()
+                .filter(|(hand, _)| target.is_none() || *hand == target)
+                .map(|(_, value)| value);
+            match state {
+                XrActionState::Float(x) => {
+                    x.update(values.map(FallbackValue::scalar).fold(0.0, f32::max))
+                }
+                XrActionState::Bool(x) => {
+                    x.update(values.map(FallbackValue::scalar).any(|value| value > 0.5))
+                }
+                XrActionState::Vec2(x) => x.update(
+                    values
+                        .filter_map(|value| match value {
+                            FallbackValue::Stick(stick) => Some(*stick),
+                            FallbackValue::Scalar(_) => None,
+                        })
+                        .sum::<Vec2>()
+                        .clamp_length_max(1.0),
+                ),
+                XrActionState::Pose(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::prelude::{DefaultActions, XrTypedAction};
+
+    /// Space squeezes the left hand, E the right and WASD is the stick on both
+    fn world() -> World {
+        let mut config = Config::default();
+        for action_config in config.actions.iter_mut() {
+            action_config.fallback = match action_config.name.as_str() {
+                "squeeze" => vec![
+                    XrFallbackBinding::on(XrFallbackInput::Key(KeyCode::Space), Hand::Left),
+                    XrFallbackBinding::on(XrFallbackInput::Key(KeyCode::KeyE), Hand::Right),
+                ],
+                "joystick" => vec![XrFallbackBinding::new(XrFallbackInput::Keys {
+                    up: KeyCode::KeyW,
+                    down: KeyCode::KeyS,
+                    left: KeyCode::KeyA,
+                    right: KeyCode::KeyD,
+                })],
+                _ => continue,
+            };
+        }
+        let mut world = World::new();
+        world.insert_resource(XrInput::from_config(&config));
+        world.insert_resource(config);
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world
+    }
+
+    fn press(world: &mut World, keys: &[KeyCode]) {
+        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        for key in keys {
+            input.press(*key);
+        }
+        world.run_system_once(update_fallback_input).unwrap();
+    }
+
+    #[test]
+    fn keys_drive_their_hand_and_the_combined_state() {
+        let mut world = world();
+        press(&mut world, &[KeyCode::Space]);
+        let inputs = world.resource::<XrInput>();
+        let squeeze = DefaultActions::Squeeze.action();
+        let squeezed = |hand| inputs.float_state(&squeeze, hand).unwrap().cur_val;
+        assert_eq!(squeezed(None), 1.0);
+        assert_eq!(squeezed(Some(Hand::Left)), 1.0);
+        assert_eq!(squeezed(Some(Hand::Right)), 0.0);
+        assert!(inputs.float_state(&squeeze, None).unwrap().pressed);
+    }
+
+    #[test]
+    fn keys_make_a_stick() {
+        let joystick = DefaultActions::Joystick.action();
+        let stick = |world: &World, hand| {
+            let state = match hand {
+                Some(hand) => world.resource::<XrInput>().get_hand(&joystick, hand),
+                None => world.resource::<XrInput>().get(&joystick),
+            };
+            state.unwrap().as_vec2().unwrap().raw_val
+        };
+        let mut world = world();
+        press(&mut world, &[KeyCode::KeyW, KeyCode::KeyD]);
+        assert!(stick(&world, None).abs_diff_eq(Vec2::ONE.normalize(), 1e-6));
+        // the binding has no hand so only the combined state sees it
+        assert_eq!(stick(&world, Some(Hand::Left)), Vec2::ZERO);
+
+        press(&mut world, &[]);
+        assert_eq!(stick(&world, None), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,276 @@
+use std::hash::Hash;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{
+    actions::{Hand, XrAction, XrActionState, XrInput},
+    contexts::XrInputContexts,
+    events::transition,
+};
+
+/// An action as seen from a fixed tick
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XrFixedActionState {
+    /// Went down since the last fixed tick, even if it was let go again before it
+    pub pressed: bool,
+    /// Went back up since the last fixed tick
+    pub released: bool,
+    pub is_pressed: bool,
+    /// Interpolated to the time of the current tick, float and bool actions only use `x`
+    pub value: Vec2,
+    previous_val: Vec2,
+    cur_val: Vec2,
+}
+
+impl XrFixedActionState {
+    /// The value of a float or bool action at the current tick
+    pub fn float(&self) -> f32 {
+        self.value.x
+    }
+}
+
+/// [`XrInput`] for systems in `FixedUpdate`. A frame can run zero or several fixed ticks so the
+/// edges are kept until a tick has run instead of being cleared every frame, and analog values
+/// are interpolated between the last two frames to when the tick happens.
+#[derive(Resource, Default, Debug)]
+pub struct XrFixedInput {
+    state: HashMap<XrAction, XrFixedActionState>,
+    hands: HashMap<(XrAction, Hand), XrFixedActionState>,
+}
+
+impl XrFixedInput {
+    pub fn get(&self, action: &XrAction) -> Option<&XrFixedActionState> {
+        self.state.get(action)
+    }
+
+    pub fn get_hand(&self, action: &XrAction, hand: Hand) -> Option<&XrFixedActionState> {
+        self.hands.get(&(action.clone(), hand))
+    }
+
+    fn states_mut(&mut self) -> impl Iterator<Item = &mut XrFixedActionState> {
+        self.state.values_mut().chain(self.hands.values_mut())
+    }
+}
+
+fn latch(fixed: &mut XrFixedActionState, state: &XrActionState) {
+    let (pressed, released, _) = transition(state);
+    let (previous_val, cur_val, is_pressed) = match state {
+        XrActionState::Float(x) => (
+            Vec2::new(x.previous_val, 0.0),
+            Vec2::new(x.cur_val, 0.0),
+            x.is_pressed,
+        ),
+        XrActionState::Bool(x) => (
+            Vec2::new(x.previous_val as i32 as f32, 0.0),
+            Vec2::new(x.cur_val as i32 as f32, 0.0),
+            x.cur_val,
+        ),
+        XrActionState::Vec2(x) => (x.previous_val, x.cur_val, x.direction.is_some()),
+        XrActionState::Pose(_) => return,
+    };
+    fixed.pressed |= pressed;
+    fixed.released |= released;
+    fixed.is_pressed = is_pressed;
+    fixed.previous_val = previous_val;
+    fixed.cur_val = cur_val;
+    fixed.value = cur_val;
+}
+
+/// Latches a consumed action as let go
+fn latch_released(fixed: &mut XrFixedActionState) {
+    fixed.released |= fixed.is_pressed;
+    fixed.is_pressed = false;
+    fixed.previous_val = fixed.cur_val;
+    fixed.cur_val = Vec2::ZERO;
+    fixed.value = Vec2::ZERO;
+}
+
+/// Runs once the frame's [`XrInput`] is final and adds its edges to the ones no tick saw yet,
+/// actions an [`XrInputContexts`] consumed are latched as released
+pub fn latch_fixed_input(
+    inputs: Option<Res<XrInput>>,
+    contexts: Res<XrInputContexts>,
+    mut fixed: ResMut<XrFixedInput>,
+) {
+    let Some(inputs) = inputs else {
+        if !fixed.state.is_empty() || !fixed.hands.is_empty() {
+            fixed.state.clear();
+            fixed.hands.clear();
+        }
+        return;
+    };
+    let fixed = fixed.as_mut();
+    for (action, state) in inputs.state.iter() {
+        latch_into(
+            &mut fixed.state,
+            action,
+            state,
+            contexts.is_consumed(action, None),
+        );
+    }
+    for (key, state) in inputs.hands.iter() {
+        latch_into(
+            &mut fixed.hands,
+            key,
+            state,
+            contexts.is_consumed(&key.0, Some(key.1)),
+        );
+    }
+}
+
+/// Latches into the state at `key`, which is only cloned the first time the action shows up
+fn latch_into<K: Clone + Eq + Hash>(
+    states: &mut HashMap<K, XrFixedActionState>,
+    key: &K,
+    state: &XrActionState,
+    consumed: bool,
+) {
+    let apply = |fixed: &mut XrFixedActionState| {
+        if consumed && !matches!(state, XrActionState::Pose(_)) {
+            latch_released(fixed);
+        } else {
+            latch(fixed, state);
+        }
+    };
+    match states.get_mut(key) {
+        Some(fixed) => apply(fixed),
+        None => {
+            let mut fixed = XrFixedActionState::default();
+            apply(&mut fixed);
+            states.insert(key.clone(), fixed);
+        }
+    }
+}
+
+/// Interpolates the values to the tick, `Time<Fixed>` is already at the end of the tick here
+pub fn interpolate_fixed_input(
+    fixed_time: Res<Time<Fixed>>,
+    virtual_time: Res<Time<Virtual>>,
+    mut fixed: ResMut<XrFixedInput>,
+) {
+    let delta = virtual_time.delta_secs_f64();
+    // how far into the last frame the tick is
+    let t = if delta > 0.0 {
+        let behind = virtual_time.elapsed_secs_f64() - fixed_time.elapsed_secs_f64();
+        (1.0 - behind / delta).clamp(0.0, 1.0) as f32
+    } else {
+        1.0
+    };
+    for state in fixed.states_mut() {
+        state.value = state.previous_val.lerp(state.cur_val, t);
+    }
+}
+
+/// The tick saw the edges, later ticks in the same frame won't
+pub fn end_fixed_tick(mut fixed: ResMut<XrFixedInput>) {
+    for state in fixed.states_mut() {
+        state.pressed = false;
+        state.released = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        input::{actions::XrActionStateVec2, joystick::XrStickDirection},
+        mock::{mock_app, XrMockFrame, XrMockInput, XrMockScript, XrMockValue},
+        prelude::{DefaultActions, XrTypedAction},
+    };
+
+    fn stick(cur_val: Vec2, direction: Option<XrStickDirection>) -> XrActionState {
+        XrActionState::Vec2(XrActionStateVec2 {
+            cur_val,
+            direction,
+            ..default()
+        })
+    }
+
+    #[test]
+    fn sticks_press_past_the_direction_threshold() {
+        let mut fixed = XrFixedActionState::default();
+        latch(&mut fixed, &stick(Vec2::new(0.0, 0.2), None));
+        assert!(!fixed.is_pressed && !fixed.pressed);
+
+        latch(
+            &mut fixed,
+            &stick(Vec2::new(0.0, 0.8), Some(XrStickDirection::N)),
+        );
+        assert!(fixed.is_pressed && fixed.pressed);
+    }
+
+    #[test]
+    fn consumed_actions_latch_as_released() {
+        let mut fixed = XrFixedActionState::default();
+        latch(
+            &mut fixed,
+            &stick(Vec2::new(0.0, 0.8), Some(XrStickDirection::N)),
+        );
+        latch_released(&mut fixed);
+        assert!(!fixed.is_pressed && fixed.released);
+        assert_eq!(fixed.value, Vec2::ZERO);
+    }
+
+    #[derive(Resource, Default)]
+    struct Ticks(Vec<XrFixedActionState>);
+
+    /// Frames 40ms apart with a tick every 100ms, the first frame has no delta so the only tick in
+    /// the first four frames is in the last one, 20ms into it
+    fn ticking_app(squeeze: &[f32]) -> App {
+        let frames = squeeze
+            .iter()
+            .map(|&value| XrMockFrame {
+                inputs: vec![XrMockInput {
+                    action: DefaultActions::Squeeze.action(),
+                    hand: None,
+                    value: XrMockValue::Float(value),
+                }],
+                ..default()
+            })
+            .collect();
+        let mut app = mock_app(XrMockScript::new(frames));
+        app.insert_resource(Time::<Fixed>::from_seconds(0.1));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            40,
+        )));
+        app.init_resource::<Ticks>();
+        app.add_systems(
+            FixedUpdate,
+            |fixed: Res<XrFixedInput>, mut ticks: ResMut<Ticks>| {
+                let squeeze = fixed.get(&DefaultActions::Squeeze.action()).unwrap();
+                ticks.0.push(*squeeze);
+            },
+        );
+        app
+    }
+
+    fn run(app: &mut App, frames: usize) -> &[XrFixedActionState] {
+        for _ in 0..frames {
+            app.update();
+        }
+        &app.world().resource::<Ticks>().0
+    }
+
+    #[test]
+    fn edges_wait_for_a_tick() {
+        let mut app = ticking_app(&[0.0, 1.0, 0.0, 0.0]);
+        assert!(run(&mut app, 3).is_empty());
+
+        let ticks = run(&mut app, 1);
+        assert_eq!(ticks.len(), 1);
+        assert!(ticks[0].pressed && ticks[0].released && !ticks[0].is_pressed);
+    }
+
+    #[test]
+    fn values_are_interpolated_to_the_tick() {
+        let mut app = ticking_app(&[0.0, 0.0, 0.0, 1.0]);
+        let ticks = run(&mut app, 4);
+        assert_eq!(ticks.len(), 1);
+        assert!((ticks[0].float() - 0.5).abs() < 1e-4);
+        assert!(ticks[0].pressed && ticks[0].is_pressed);
+    }
+}
@@ -0,0 +1,361 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_mod_openxr::session::OxrSession;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    actions::{Hand, XrAction, XrActions},
+    config::Config,
+};
+use crate::error::{ReportXrError, XrSubsystem, XrUtilsError};
+
+/// Amplitude, frequency and duration of a vibration without a hand, see [`XrHaptic::on`]
+#[derive(Clone, Copy, Debug)]
+pub struct XrHaptic {
+    /// 0.0 to 1.0
+    pub amplitude: f32,
+    /// In hz, 0.0 lets the runtime pick
+    pub frequency: f32,
+    pub duration: Duration,
+}
+
+impl XrHaptic {
+    pub fn on(&self, hand: Hand) -> XrHapticPulse {
+        XrHapticPulse {
+            hand,
+            amplitude: self.amplitude,
+            frequency: self.frequency,
+            duration: self.duration,
+        }
+    }
+}
+
+/// Vibrates `hand` through every haptic action in [`XrActions`], replaces any pulse still playing on it
+#[derive(Event, Clone, Copy, Debug)]
+pub struct XrHapticPulse {
+    pub hand: Hand,
+    /// 0.0 to 1.0
+    pub amplitude: f32,
+    /// In hz, 0.0 lets the runtime pick
+    pub frequency: f32,
+    pub duration: Duration,
+}
+
+/// Stops whatever pulse is playing on `hand`
+#[derive(Event, Clone, Copy, Debug)]
+pub struct XrStopHaptics {
+    pub hand: Hand,
+}
+
+fn hand_path(actions: &XrActions, action: &XrAction, hand: Hand) -> Option<openxr::Path> {
+    let paths = actions.subaction_paths(action);
+    if paths.is_empty() {
+        return Some(openxr::Path::NULL);
+    }
+    paths
+        .into_iter()
+        .find(|(subaction_hand, _)| *subaction_hand == hand)
+        .map(|(_, path)| path)
+}
+
+pub fn apply_haptics(
+    actions: Option<Res<XrActions>>,
+    session: Option<Res<OxrSession>>,
+    mut pulses: EventReader<XrHapticPulse>,
+    mut stops: EventReader<XrStopHaptics>,
+    mut cmds: Commands,
+) {
+    let (Some(actions), Some(session)) = (actions, session) else {
+        pulses.clear();
+        stops.clear();
+        return;
+    };
+    for stop in stops.read() {
+        for (action, haptic) in actions.haptic_actions() {
+            let Some(path) = hand_path(&actions, action, stop.hand) else {
+                continue;
+            };
+            if let Err(result) = haptic.stop_feedback(&session, path) {
+                cmds.queue(ReportXrError::warn(
+                    XrSubsystem::Haptics,
+                    XrUtilsError::Runtime {
+                        what: format!("failed to stop haptics on {:?}", stop.hand),
+                        result,
+                    },
+                ));
+            }
+        }
+    }
+    for pulse in pulses.read() {
+        let vibration = openxr::HapticVibration::new()
+            .amplitude(pulse.amplitude.clamp(0.0, 1.0))
+            .frequency(pulse.frequency)
+            .duration(openxr::Duration::from_nanos(
+                pulse.duration.as_nanos() as i64
+            ));
+        for (action, haptic) in actions.haptic_actions() {
+            let Some(path) = hand_path(&actions, action, pulse.hand) else {
+                continue;
+            };
+            if let Err(result) = haptic.apply_feedback(&session, path, &vibration) {
+                cmds.queue(ReportXrError::warn(
+                    XrSubsystem::Haptics,
+                    XrUtilsError::Runtime {
+                        what: format!("failed to apply haptics on {:?}", pulse.hand),
+                        result,
+                    },
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum XrHapticEasing {
+    #[default]
+    Linear,
+    /// Holds each keyframe until the next one
+    Step,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl XrHapticEasing {
+    fn ease(&self, t: f32) -> f32 {
+        match self {
+            XrHapticEasing::Linear => t,
+            XrHapticEasing::Step => 0.0,
+            XrHapticEasing::EaseIn => t * t,
+            XrHapticEasing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            XrHapticEasing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct XrHapticKeyframe {
+    /// Seconds since the start of the pattern
+    pub time: f32,
+    /// 0.0 to 1.0
+    pub amplitude: f32,
+    /// In hz, 0.0 lets the runtime pick
+    #[serde(default)]
+    pub frequency: f32,
+}
+
+/// A named vibration envelope, declared under `[[haptic_patterns]]` in the action config
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct XrHapticPattern {
+    pub name: String,
+    /// Sorted by time
+    pub keyframes: Vec<XrHapticKeyframe>,
+    #[serde(default)]
+    pub easing: XrHapticEasing,
+    #[serde(default)]
+    pub looping: bool,
+    /// Patterns with a higher or equal priority preempt the one playing on a hand
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl XrHapticPattern {
+    pub fn length(&self) -> f32 {
+        self.keyframes.last().map(|key| key.time).unwrap_or(0.0)
+    }
+
+    /// Amplitude and frequency at `time` seconds into the pattern
+    pub fn sample(&self, time: f32) -> (f32, f32) {
+        let Some(first) = self.keyframes.first() else {
+            return (0.0, 0.0);
+        };
+        if time <= first.time {
+            return (first.amplitude, first.frequency);
+        }
+        for window in self.keyframes.windows(2) {
+            let (from, to) = (window[0], window[1]);
+            if time < to.time {
+                let span = to.time - from.time;
+                let t = if span > 0.0 {
+                    self.easing.ease((time - from.time) / span)
+                } else {
+                    1.0
+                };
+                return (
+                    from.amplitude + (to.amplitude - from.amplitude) * t,
+                    from.frequency + (to.frequency - from.frequency) * t,
+                );
+            }
+        }
+        let last = self.keyframes.last().unwrap_or(first);
+        (last.amplitude, last.frequency)
+    }
+}
+
+/// Plays the pattern called `pattern` from the [`Config`] on `hand`
+#[derive(Event, Clone, Debug)]
+pub struct XrPlayHapticPattern {
+    pub hand: Hand,
+    pub pattern: String,
+}
+
+/// Stops the pattern playing on `hand`
+#[derive(Event, Clone, Copy, Debug)]
+pub struct XrStopHapticPattern {
+    pub hand: Hand,
+}
+
+struct XrPlayingPattern {
+    pattern: XrHapticPattern,
+    elapsed: f32,
+}
+
+/// The pattern currently playing on each hand
+#[derive(Resource, Default)]
+pub struct XrHapticPlayers {
+    playing: HashMap<Hand, XrPlayingPattern>,
+}
+
+impl XrHapticPlayers {
+    pub fn playing(&self, hand: Hand) -> Option<&str> {
+        self.playing
+            .get(&hand)
+            .map(|playing| playing.pattern.name.as_str())
+    }
+}
+
+pub fn play_haptic_patterns(
+    config: Res<Config>,
+    time: Res<Time>,
+    mut players: ResMut<XrHapticPlayers>,
+    mut play_events: EventReader<XrPlayHapticPattern>,
+    mut stop_events: EventReader<XrStopHapticPattern>,
+    mut pulses: EventWriter<XrHapticPulse>,
+    mut stops: EventWriter<XrStopHaptics>,
+) {
+    for stop in stop_events.read() {
+        if players.playing.remove(&stop.hand).is_some() {
+            stops.send(XrStopHaptics { hand: stop.hand });
+        }
+    }
+    for play in play_events.read() {
+        let Some(pattern) = config
+            .haptic_patterns
+            .iter()
+            .find(|pattern| pattern.name == play.pattern)
+        else {
+            warn!("no haptic pattern called {}", play.pattern);
+            continue;
+        };
+        if let Some(playing) = players.playing.get(&play.hand) {
+            if playing.pattern.priority > pattern.priority {
+                continue;
+            }
+        }
+        players.playing.insert(
+            play.hand,
+            XrPlayingPattern {
+                pattern: pattern.clone(),
+                elapsed: 0.0,
+            },
+        );
+    }
+
+    let delta = time.delta_secs();
+    players.playing.retain(|hand, playing| {
+        let length = playing.pattern.length();
+        if playing.elapsed > length {
+            if !playing.pattern.looping || length <= 0.0 {
+                stops.send(XrStopHaptics { hand: *hand });
+                return false;
+            }
+            playing.elapsed %= length;
+        }
+        let (amplitude, frequency) = playing.pattern.sample(playing.elapsed);
+        // lasts a bit longer than a frame so there are no gaps, the next pulse replaces it
+        pulses.send(XrHapticPulse {
+            hand: *hand,
+            amplitude,
+            frequency,
+            duration: Duration::from_secs_f32((delta * 2.0).max(0.01)),
+        });
+        playing.elapsed += delta;
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn pattern(name: &str, easing: XrHapticEasing, priority: i32) -> XrHapticPattern {
+        let key = |time, amplitude, frequency| XrHapticKeyframe {
+            time,
+            amplitude,
+            frequency,
+        };
+        XrHapticPattern {
+            name: name.to_string(),
+            keyframes: vec![key(0.0, 0.0, 0.0), key(1.0, 1.0, 100.0), key(2.0, 0.0, 0.0)],
+            easing,
+            looping: false,
+            priority,
+        }
+    }
+
+    #[test]
+    fn samples_the_start_middle_and_end() {
+        let pattern = pattern("pulse", XrHapticEasing::Linear, 0);
+        assert_eq!(pattern.sample(-1.0), (0.0, 0.0));
+        assert_eq!(pattern.sample(0.0), (0.0, 0.0));
+        assert_eq!(pattern.sample(0.5), (0.5, 50.0));
+        assert_eq!(pattern.sample(1.0), (1.0, 100.0));
+        assert_eq!(pattern.sample(1.5), (0.5, 50.0));
+        assert_eq!(pattern.sample(2.0), (0.0, 0.0));
+        assert_eq!(pattern.sample(3.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn easing_shapes_the_middle() {
+        let at_half = |easing| pattern("pulse", easing, 0).sample(0.5).0;
+        assert_eq!(at_half(XrHapticEasing::Step), 0.0);
+        assert_eq!(at_half(XrHapticEasing::EaseIn), 0.25);
+        assert_eq!(at_half(XrHapticEasing::EaseOut), 0.75);
+        assert_eq!(at_half(XrHapticEasing::EaseInOut), 0.5);
+    }
+
+    #[test]
+    fn higher_priority_replaces_lower() {
+        let mut config = Config::new("main", "Main", Vec::new());
+        config.haptic_patterns = vec![
+            pattern("buzz", XrHapticEasing::Linear, 0),
+            pattern("alarm", XrHapticEasing::Linear, 1),
+        ];
+        let mut world = World::new();
+        world.insert_resource(config);
+        world.init_resource::<Time>();
+        world.init_resource::<XrHapticPlayers>();
+        world.init_resource::<Events<XrPlayHapticPattern>>();
+        world.init_resource::<Events<XrStopHapticPattern>>();
+        world.init_resource::<Events<XrHapticPulse>>();
+        world.init_resource::<Events<XrStopHaptics>>();
+        let mut play = |pattern: &str| {
+            world.send_event(XrPlayHapticPattern {
+                hand: Hand::Left,
+                pattern: pattern.to_string(),
+            });
+            world.run_system_once(play_haptic_patterns).unwrap();
+            world.resource_mut::<Events<XrPlayHapticPattern>>().clear();
+            world
+                .resource::<XrHapticPlayers>()
+                .playing(Hand::Left)
+                .map(str::to_string)
+        };
+        assert_eq!(play("buzz").as_deref(), Some("buzz"));
+        assert_eq!(play("alarm").as_deref(), Some("alarm"));
+        assert_eq!(play("buzz").as_deref(), Some("alarm"));
+    }
+}
@@ -0,0 +1,203 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_mod_openxr::{resources::OxrInstance, session::OxrSession};
+
+use super::{
+    action_sets::XrActiveActionSets,
+    actions::{Hand, XrAction, XrActions, XrActionsAttached},
+};
+use crate::error::{ReportXrError, XrSubsystem, XrUtilsError};
+
+/// The interaction profile the runtime picked for each hand, like
+/// `/interaction_profiles/oculus/touch_controller`. Missing while nothing is held or before the
+/// action set is attached.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct XrInteractionProfiles {
+    profiles: HashMap<Hand, String>,
+    /// What the runtime answered last, compared before turning it into a string
+    paths: HashMap<Hand, openxr::Path>,
+}
+
+impl XrInteractionProfiles {
+    pub fn get(&self, hand: Hand) -> Option<&str> {
+        self.profiles.get(&hand).map(String::as_str)
+    }
+}
+
+/// Sent when the runtime switches the interaction profile of a hand
+#[derive(Event, Clone, Debug)]
+pub struct XrInteractionProfileChanged {
+    pub hand: Hand,
+    pub previous: Option<String>,
+    pub current: Option<String>,
+}
+
+/// Asks the runtime for the current profiles after every sync. bevy_mod_openxr polls the event
+/// queue itself and doesn't pass `XR_TYPE_EVENT_DATA_INTERACTION_PROFILE_CHANGED` on, so the
+/// change is seen as a different answer here instead. Each frame only compares the returned path
+/// handles, the profile name is looked up when one changes. Only valid once the set is attached.
+pub fn update_interaction_profiles(
+    actions: Option<Res<XrActions>>,
+    attached: Option<Res<XrActionsAttached>>,
+    session: Option<Res<OxrSession>>,
+    instance: Option<Res<OxrInstance>>,
+    mut profiles: ResMut<XrInteractionProfiles>,
+    mut changed: EventWriter<XrInteractionProfileChanged>,
+) {
+    let (Some(actions), Some(_), Some(session), Some(instance)) =
+        (actions, attached, session, instance)
+    else {
+        return;
+    };
+    for hand in Hand::ALL {
+        let Some(path) = actions.hand_path(hand) else {
+            continue;
+        };
+        let profile = session
+            .current_interaction_profile(path)
+            .unwrap_or(openxr::Path::NULL);
+        if profiles
+            .paths
+            .get(&hand)
+            .copied()
+            .unwrap_or(openxr::Path::NULL)
+            == profile
+        {
+            continue;
+        }
+        profiles.paths.insert(hand, profile);
+        let current = if profile == openxr::Path::NULL {
+            None
+        } else {
+            instance.path_to_string(profile).ok()
+        };
+        if profiles.profiles.get(&hand) == current.as_ref() {
+            continue;
+        }
+        let previous = match &current {
+            Some(current) => profiles.profiles.insert(hand, current.clone()),
+            None => profiles.profiles.remove(&hand),
+        };
+        changed.send(XrInteractionProfileChanged {
+            hand,
+            previous,
+            current,
+        });
+    }
+}
+
+/// Forgets the profiles and bound sources when the session goes away
+pub fn clear_interaction_profiles(
+    mut profiles: ResMut<XrInteractionProfiles>,
+    mut sources: ResMut<XrBoundSources>,
+    mut changed: EventWriter<XrInteractionProfileChanged>,
+) {
+    sources.sources.clear();
+    profiles.paths.clear();
+    for (hand, previous) in profiles.profiles.drain() {
+        changed.send(XrInteractionProfileChanged {
+            hand,
+            previous: Some(previous),
+            current: None,
+        });
+    }
+}
+
+/// A physical input an action is bound to
+#[derive(Clone, Debug)]
+pub struct XrBoundSource {
+    /// Like `/user/hand/right/input/a/click`
+    pub path: String,
+    /// Just the component, like "A Button"
+    pub name: String,
+    /// Includes the hand, like "Right Hand A Button"
+    pub full_name: String,
+}
+
+/// Localized names of what every action is bound to, for button prompts. Refreshed whenever an
+/// interaction profile changes.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct XrBoundSources {
+    sources: HashMap<XrAction, Vec<XrBoundSource>>,
+}
+
+impl XrBoundSources {
+    pub fn get(&self, action: &XrAction) -> &[XrBoundSource] {
+        self.sources.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Sources of `action` on `hand`
+    pub fn for_hand<'a>(
+        &'a self,
+        action: &XrAction,
+        hand: Hand,
+    ) -> impl Iterator<Item = &'a XrBoundSource> {
+        self.get(action)
+            .iter()
+            .filter(move |source| source.path.starts_with(hand.path()))
+    }
+}
+
+/// Refreshes [`XrBoundSources`] when a profile changes, the actions are replaced or they get
+/// attached, the runtime only knows the sources of attached actions. Also refreshed when sets are
+/// enabled or reprioritized since runtimes only report sources for synced sets.
+#[allow(clippy::too_many_arguments)]
+pub fn update_bound_sources(
+    actions: Option<Res<XrActions>>,
+    attached: Option<Res<XrActionsAttached>>,
+    active: Res<XrActiveActionSets>,
+    session: Option<Res<OxrSession>>,
+    instance: Option<Res<OxrInstance>>,
+    mut sources: ResMut<XrBoundSources>,
+    mut changed: EventReader<XrInteractionProfileChanged>,
+    mut cmds: Commands,
+) {
+    let profile_changed = changed.read().count() > 0;
+    let actions_changed = actions.as_ref().is_some_and(|actions| actions.is_changed());
+    let just_attached = attached
+        .as_ref()
+        .is_some_and(|attached| attached.is_changed());
+    if !profile_changed && !actions_changed && !just_attached && !active.is_changed() {
+        return;
+    }
+    let (Some(actions), Some(_), Some(session), Some(instance)) =
+        (actions, attached, session, instance)
+    else {
+        return;
+    };
+    sources.sources.clear();
+    for (action, raw) in actions.raw_actions() {
+        let paths = match raw.bound_sources(&session) {
+            Ok(paths) => paths,
+            Err(result) => {
+                cmds.queue(ReportXrError::warn(
+                    XrSubsystem::Actions,
+                    XrUtilsError::Runtime {
+                        what: format!("failed to get bound sources of {}", action.name),
+                        result,
+                    },
+                ));
+                continue;
+            }
+        };
+        let mut bound = Vec::new();
+        for path in paths {
+            let name = |flags| session.input_source_localized_name(path, flags).ok();
+            let (Ok(path_string), Some(name), Some(full_name)) = (
+                instance.path_to_string(path),
+                name(openxr::InputSourceLocalizedNameFlags::COMPONENT),
+                name(
+                    openxr::InputSourceLocalizedNameFlags::USER_PATH
+                        | openxr::InputSourceLocalizedNameFlags::COMPONENT,
+                ),
+            ) else {
+                continue;
+            };
+            bound.push(XrBoundSource {
+                path: path_string,
+                name,
+                full_name,
+            });
+        }
+        sources.sources.insert(action.clone(), bound);
+    }
+}
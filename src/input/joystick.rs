@@ -0,0 +1,238 @@
+use std::f32::consts::{FRAC_PI_4, FRAC_PI_8, TAU};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the raw value of a Vec2 action is cleaned up before it lands in `cur_val`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct XrJoystickSettings {
+    /// Lengths at or below this read as centered, hides stick drift
+    pub inner_deadzone: f32,
+    /// Lengths at or above this read as fully pushed
+    pub outer_deadzone: f32,
+    /// Each axis closer to zero than this is snapped to zero, makes pure up or sideways easier
+    /// and keeps drift on one axis from firing `pressed_x` or `pressed_y`
+    pub axial_deadzone: f32,
+    /// The length between the deadzones is raised to this, above 1.0 gives finer control near
+    /// the center
+    pub exponent: f32,
+    /// Length the stick has to pass to point in a [`XrStickDirection`]
+    pub direction_press: f32,
+    /// Length the stick has to fall to for the direction to go back to `None`
+    pub direction_release: f32,
+    /// Radians past the edge of a direction's sector the stick can go before it switches over
+    pub direction_hysteresis: f32,
+}
+
+impl Default for XrJoystickSettings {
+    fn default() -> Self {
+        Self {
+            inner_deadzone: 0.1,
+            outer_deadzone: 1.0,
+            axial_deadzone: 0.1,
+            exponent: 1.0,
+            direction_press: 0.5,
+            direction_release: 0.4,
+            direction_hysteresis: 0.1,
+        }
+    }
+}
+
+impl XrJoystickSettings {
+    /// Applies the deadzones and response curve to a raw stick value
+    pub fn process(&self, raw: Vec2) -> Vec2 {
+        let mut value = raw;
+        if value.x.abs() < self.axial_deadzone {
+            value.x = 0.0;
+        }
+        if value.y.abs() < self.axial_deadzone {
+            value.y = 0.0;
+        }
+        let length = value.length();
+        if length <= self.inner_deadzone {
+            return Vec2::ZERO;
+        }
+        let range = (self.outer_deadzone - self.inner_deadzone).max(f32::EPSILON);
+        let scaled = ((length - self.inner_deadzone) / range)
+            .clamp(0.0, 1.0)
+            .powf(self.exponent);
+        value / length * scaled
+    }
+
+    /// The direction `value` points in, sticking with `current` until it clearly leaves it
+    pub fn direction(
+        &self,
+        value: Vec2,
+        current: Option<XrStickDirection>,
+    ) -> Option<XrStickDirection> {
+        let length = value.length();
+        match current {
+            Some(current) if length > self.direction_release => {
+                let angle = XrStickDirection::angle_of(value);
+                if angle_between(angle, current.angle()) <= FRAC_PI_8 + self.direction_hysteresis {
+                    Some(current)
+                } else {
+                    Some(XrStickDirection::from_angle(angle))
+                }
+            }
+            _ if length > self.direction_press => Some(XrStickDirection::from_angle(
+                XrStickDirection::angle_of(value),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn angle_between(a: f32, b: f32) -> f32 {
+    let diff = (a - b).rem_euclid(TAU);
+    diff.min(TAU - diff)
+}
+
+/// One of the eight directions a stick can point in, up on the stick is `N`
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum XrStickDirection {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl XrStickDirection {
+    /// Clockwise from `N`
+    pub const ALL: [XrStickDirection; 8] = [
+        XrStickDirection::N,
+        XrStickDirection::NE,
+        XrStickDirection::E,
+        XrStickDirection::SE,
+        XrStickDirection::S,
+        XrStickDirection::SW,
+        XrStickDirection::W,
+        XrStickDirection::NW,
+    ];
+
+    /// Radians clockwise from up
+    pub fn angle(&self) -> f32 {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        index as f32 * FRAC_PI_4
+    }
+
+    fn angle_of(value: Vec2) -> f32 {
+        value.x.atan2(value.y)
+    }
+
+    fn from_angle(angle: f32) -> Self {
+        let index = (angle.rem_euclid(TAU) / FRAC_PI_4).round() as usize % 8;
+        Self::ALL[index]
+    }
+
+    /// Unit vector pointing this way
+    pub fn vec2(&self) -> Vec2 {
+        let angle = self.angle();
+        Vec2::new(angle.sin(), angle.cos())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::actions::XrActionStateVec2;
+
+    /// A stick pushed `length` towards `degrees` clockwise from up
+    fn stick(degrees: f32, length: f32) -> Vec2 {
+        let angle = degrees.to_radians();
+        Vec2::new(angle.sin(), angle.cos()) * length
+    }
+
+    #[test]
+    fn default_settings_ignore_drift_on_one_axis() {
+        let settings = XrJoystickSettings::default();
+        let value = settings.process(Vec2::new(0.05, 0.8));
+        assert_eq!(value.x, 0.0);
+        assert!(value.y > 0.0);
+        assert_eq!(settings.process(Vec2::new(0.08, 0.08)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn direction_sticks_past_the_sector_edge() {
+        let settings = XrJoystickSettings::default();
+        let n = Some(XrStickDirection::N);
+        let ne = Some(XrStickDirection::NE);
+        // the edge between N and NE is at 22.5 degrees, the hysteresis adds about 5.7
+        assert_eq!(settings.direction(stick(25.0, 0.8), None), ne);
+        assert_eq!(settings.direction(stick(25.0, 0.8), n), n);
+        assert_eq!(settings.direction(stick(30.0, 0.8), n), ne);
+        assert_eq!(settings.direction(stick(20.0, 0.8), ne), ne);
+        assert_eq!(settings.direction(stick(15.0, 0.8), ne), n);
+        // wraps around past up
+        assert_eq!(settings.direction(stick(-25.0, 0.8), n), n);
+        assert_eq!(
+            settings.direction(stick(-30.0, 0.8), n),
+            Some(XrStickDirection::NW)
+        );
+    }
+
+    #[test]
+    fn direction_releases_below_the_press_length() {
+        let settings = XrJoystickSettings::default();
+        let n = Some(XrStickDirection::N);
+        assert_eq!(settings.direction(stick(0.0, 0.45), None), None);
+        assert_eq!(settings.direction(stick(0.0, 0.55), None), n);
+        assert_eq!(settings.direction(stick(0.0, 0.45), n), n);
+        assert_eq!(settings.direction(stick(0.0, 0.35), n), None);
+    }
+
+    #[test]
+    fn direction_edges() {
+        let mut state = XrActionStateVec2::default();
+        let edges: Vec<_> = [
+            stick(0.0, 0.9),
+            stick(0.0, 0.9),
+            stick(90.0, 0.9),
+            Vec2::ZERO,
+        ]
+        .into_iter()
+        .map(|raw| {
+            state.update(raw);
+            let edges = (state.direction_pressed(), state.direction_released());
+            // what the end of the frame does
+            state.previous_direction = state.direction;
+            state.previous_val = state.cur_val;
+            edges
+        })
+        .collect();
+        assert_eq!(
+            edges,
+            [
+                (Some(XrStickDirection::N), None),
+                (None, None),
+                (Some(XrStickDirection::E), Some(XrStickDirection::N)),
+                (None, Some(XrStickDirection::E)),
+            ]
+        );
+    }
+
+    #[test]
+    fn response_curve() {
+        let settings = XrJoystickSettings {
+            inner_deadzone: 0.1,
+            outer_deadzone: 0.9,
+            exponent: 2.0,
+            ..default()
+        };
+        // halfway between the deadzones, squared
+        let value = settings.process(Vec2::new(0.0, 0.5));
+        assert!((value.y - 0.25).abs() < 1e-5, "{value}");
+        assert_eq!(value.x, 0.0);
+        assert_eq!(settings.process(Vec2::new(0.0, 0.95)), Vec2::Y);
+        assert_eq!(settings.process(Vec2::new(0.0, 0.1)), Vec2::ZERO);
+        // the direction is kept, only the length is curved
+        let diagonal = settings.process(stick(45.0, 0.5));
+        assert!((diagonal.x - diagonal.y).abs() < 1e-5, "{diagonal}");
+        assert!((diagonal.length() - 0.25).abs() < 1e-5, "{diagonal}");
+    }
+}
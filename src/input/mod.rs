@@ -1,16 +1,49 @@
+pub mod action_sets;
 pub mod actions;
+pub mod button_input;
+pub mod config;
+pub mod contexts;
+pub mod events;
+pub mod fallback;
+pub mod fixed;
+pub mod haptics;
+pub mod interaction_profile;
+pub mod joystick;
+pub mod profiles;
+pub mod rebinding;
+pub mod typed;
+pub mod validation;
 
+use crate::error::{subsystem_enabled, XrSubsystem};
+use action_sets::{emulate_set_priorities, XrActiveActionSets};
 use actions::{
-    attach_set, create_actions, create_input, end_frame_input, spawn_tracking_rig,
-    suggest_action_bindings, sync_actions, update_head_transforms, update_inputs,
-    update_local_floor_transforms, update_spaces, update_stage, update_view, Config, XrInput,
+    attach_set, create_actions, create_input, end_frame_input, reload_actions, spawn_tracking_rig,
+    suggest_action_bindings, sync_actions, teardown_actions, tick_action_timers,
+    update_head_transforms, update_inputs, update_local_floor_transforms, update_pose_states,
+    update_pose_world, update_spaces, update_stage, update_view, XrActions, XrInput,
 };
-use bevy::prelude::*;
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
 use bevy_mod_openxr::{
     action_binding::OxrSendActionBindings, action_set_syncing::OxrActionSetSyncSet,
     openxr_session_available, openxr_session_running, session::OxrSession, spaces::OxrSpaceSyncSet,
 };
-use bevy_mod_xr::session::{session_available, session_running, XrSessionCreated};
+use bevy_mod_xr::session::{
+    session_available, session_running, XrPreDestroySession, XrSessionCreated,
+};
+use button_input::{update_button_input, XrAxis, XrButton};
+use config::{apply_config_asset, Config, ConfigLoader};
+use contexts::{XrInputContextSet, XrInputContexts};
+use events::{send_action_events, XrActionEvent};
+use fixed::{end_fixed_tick, interpolate_fixed_input, latch_fixed_input, XrFixedInput};
+use haptics::{
+    apply_haptics, play_haptic_patterns, XrHapticPlayers, XrHapticPulse, XrPlayHapticPattern,
+    XrStopHapticPattern, XrStopHaptics,
+};
+use interaction_profile::{
+    clear_interaction_profiles, update_bound_sources, update_interaction_profiles, XrBoundSources,
+    XrInteractionProfileChanged, XrInteractionProfiles,
+};
+use validation::validate_config;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
 pub enum XrSystemSet {
@@ -23,12 +56,20 @@ pub struct OpenXRPlugin;
 impl Plugin for OpenXRPlugin {
     fn build(&self, app: &mut App) {
         // app.add_systems(XrSessionCreated, spawn_hands);
-        app.insert_resource(Config::default());
+        if !app.world().contains_resource::<Config>() {
+            app.insert_resource(Config::default());
+        }
+        if app.world().contains_resource::<AssetServer>() {
+            app.init_asset::<Config>();
+            app.register_asset_loader(ConfigLoader);
+            app.add_systems(PreUpdate, apply_config_asset.before(reload_actions));
+        }
+        app.add_systems(PreUpdate, reload_actions.before(XrSystemSet::Init));
+        app.add_systems(XrPreDestroySession, teardown_actions);
         app.configure_sets(Startup, XrSystemSet::Init.run_if(openxr_session_available));
-        app.configure_sets(
-            PreUpdate,
-            XrSystemSet::Update.run_if(openxr_session_running),
-        );
+        for schedule in [PreUpdate.intern(), PostUpdate.intern()] {
+            app.configure_sets(schedule, XrSystemSet::Update.run_if(openxr_session_running));
+        }
         app.add_systems(XrSessionCreated, attach_set);
         app.add_systems(
             PreUpdate,
@@ -36,6 +77,15 @@ impl Plugin for OpenXRPlugin {
                 .before(OxrActionSetSyncSet)
                 .run_if(openxr_session_running),
         );
+        app.init_resource::<XrActiveActionSets>();
+        app.init_resource::<XrInputContexts>();
+        app.add_systems(
+            PreUpdate,
+            emulate_set_priorities
+                .after(update_bound_sources)
+                .before(update_inputs)
+                .in_set(XrSystemSet::Update),
+        );
         app.add_systems(PreUpdate, update_view.after(update_head_transforms));
 
         //local floor transforms
@@ -44,7 +94,10 @@ impl Plugin for OpenXRPlugin {
             update_local_floor_transforms.after(update_head_transforms),
         );
         //spawn tracking rig
-        app.add_systems(XrSessionCreated, spawn_tracking_rig);
+        app.add_systems(
+            XrSessionCreated,
+            spawn_tracking_rig.run_if(subsystem_enabled(XrSubsystem::TrackingRig)),
+        );
 
         //update stage transforms
         //external
@@ -63,8 +116,11 @@ impl Plugin for OpenXRPlugin {
             Startup,
             create_actions
                 .before(create_input)
-                .run_if(session_available),
+                .after(validate_config)
+                .run_if(session_available)
+                .run_if(subsystem_enabled(XrSubsystem::Actions)),
         );
+        app.add_systems(Startup, validate_config);
         app.add_systems(
             PreUpdate,
             create_input
@@ -73,12 +129,78 @@ impl Plugin for OpenXRPlugin {
                 .run_if(run_if_no_input),
         );
         app.add_systems(PreUpdate, update_spaces.after(OxrSpaceSyncSet));
-        app.add_systems(PreUpdate, update_inputs.in_set(XrSystemSet::Update));
-        app.add_systems(PostUpdate, end_frame_input.in_set(XrSystemSet::Update));
+        app.add_systems(
+            PreUpdate,
+            update_inputs
+                .in_set(XrSystemSet::Update)
+                .run_if(subsystem_enabled(XrSubsystem::Actions)),
+        );
+        app.add_event::<XrActionEvent>();
+        app.init_resource::<XrInteractionProfiles>();
+        app.add_event::<XrInteractionProfileChanged>();
+        app.add_systems(
+            PreUpdate,
+            update_interaction_profiles
+                .after(OxrActionSetSyncSet)
+                .in_set(XrSystemSet::Update),
+        );
+        app.init_resource::<XrBoundSources>();
+        app.add_systems(
+            PreUpdate,
+            update_bound_sources.after(update_interaction_profiles),
+        );
+        app.add_systems(XrPreDestroySession, clear_interaction_profiles);
+        app.add_systems(
+            PreUpdate,
+            update_pose_states
+                .after(update_inputs)
+                .after(OxrSpaceSyncSet)
+                .in_set(XrSystemSet::Update),
+        );
+        app.add_systems(PreUpdate, update_pose_world.after(update_pose_states));
+        app.add_systems(
+            PreUpdate,
+            tick_action_timers
+                .after(update_inputs)
+                .after(update_pose_world),
+        );
+        app.add_systems(PreUpdate, send_action_events.after(tick_action_timers));
+        app.init_resource::<ButtonInput<XrButton>>();
+        app.init_resource::<Axis<XrAxis>>();
+        app.configure_sets(PreUpdate, XrInputContextSet.after(tick_action_timers));
+        app.add_systems(PreUpdate, update_button_input.after(XrInputContextSet));
+        app.init_resource::<XrFixedInput>();
+        app.add_systems(PreUpdate, latch_fixed_input.after(XrInputContextSet));
+        app.add_systems(FixedFirst, interpolate_fixed_input);
+        app.add_systems(FixedPostUpdate, end_fixed_tick);
+        // every backend's input ends its frame here, whether a session runs or not
+        app.add_systems(PostUpdate, end_frame_input);
+        app.add_event::<XrHapticPulse>();
+        app.add_event::<XrStopHaptics>();
+        app.add_event::<XrPlayHapticPattern>();
+        app.add_event::<XrStopHapticPattern>();
+        app.init_resource::<XrHapticPlayers>();
+        app.add_systems(
+            PreUpdate,
+            play_haptic_patterns
+                .after(update_inputs)
+                .in_set(XrSystemSet::Update)
+                .run_if(subsystem_enabled(XrSubsystem::Haptics)),
+        );
+        app.add_systems(
+            PostUpdate,
+            apply_haptics
+                .in_set(XrSystemSet::Update)
+                .run_if(subsystem_enabled(XrSubsystem::Haptics)),
+        );
     }
 }
 
-fn run_if_no_input(input: Option<Res<XrInput>>, session: Option<Res<OxrSession>>) -> bool {
+fn run_if_no_input(
+    input: Option<Res<XrInput>>,
+    actions: Option<Res<XrActions>>,
+    session: Option<Res<OxrSession>>,
+) -> bool {
     // true
-    input.is_none() && session.is_some()
+    input.is_none() && actions.is_some() && session.is_some()
 }
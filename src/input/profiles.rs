@@ -0,0 +1,379 @@
+use bevy_mod_openxr::resources::OxrInstance;
+
+use super::{actions::Hand, config::XrBinding};
+use XrComponentKind::*;
+
+pub const OCULUS_TOUCH: &str = "/interaction_profiles/oculus/touch_controller";
+pub const VALVE_INDEX: &str = "/interaction_profiles/valve/index_controller";
+pub const HTC_VIVE: &str = "/interaction_profiles/htc/vive_controller";
+pub const MICROSOFT_MOTION: &str = "/interaction_profiles/microsoft/motion_controller";
+/// Needs `XR_BD_controller_interaction` enabled, bindings for it are skipped otherwise
+pub const PICO_NEO3: &str = "/interaction_profiles/bytedance/pico_neo3_controller";
+/// Needs `XR_BD_controller_interaction` enabled, bindings for it are skipped otherwise
+pub const PICO4: &str = "/interaction_profiles/bytedance/pico4_controller";
+pub const KHR_SIMPLE: &str = "/interaction_profiles/khr/simple_controller";
+
+/// Logical inputs every preset provides a component for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum XrPresetInput {
+    GripPose,
+    AimPose,
+    Squeeze,
+    Trigger,
+    Joystick,
+    Haptic,
+}
+
+/// Component paths (relative to the hand) a profile uses for each [`XrPresetInput`]
+#[derive(Clone, Copy, Debug)]
+pub struct XrProfilePreset {
+    pub interaction_profile: &'static str,
+    pub grip_pose: &'static str,
+    pub aim_pose: &'static str,
+    pub squeeze: &'static str,
+    pub trigger: &'static str,
+    /// `None` if the profile has nothing that works as a joystick
+    pub joystick: Option<&'static str>,
+    pub haptic: &'static str,
+}
+
+impl XrProfilePreset {
+    pub fn component(&self, input: XrPresetInput) -> Option<&'static str> {
+        match input {
+            XrPresetInput::GripPose => Some(self.grip_pose),
+            XrPresetInput::AimPose => Some(self.aim_pose),
+            XrPresetInput::Squeeze => Some(self.squeeze),
+            XrPresetInput::Trigger => Some(self.trigger),
+            XrPresetInput::Joystick => self.joystick,
+            XrPresetInput::Haptic => Some(self.haptic),
+        }
+    }
+}
+
+/// Fallbacks used where a profile is missing a component:
+/// - vive wands have no thumbstick so the trackpad is used as the joystick
+/// - vive wands and wmr controllers only have a squeeze click, the runtime reports it as 0.0 or 1.0
+/// - the simple controller has no squeeze or trigger so both use select, and has no joystick at all
+pub const PROFILE_PRESETS: &[XrProfilePreset] = &[
+    XrProfilePreset {
+        interaction_profile: OCULUS_TOUCH,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/squeeze/value",
+        trigger: "input/trigger/value",
+        joystick: Some("input/thumbstick"),
+        haptic: "output/haptic",
+    },
+    XrProfilePreset {
+        interaction_profile: VALVE_INDEX,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/squeeze/value",
+        trigger: "input/trigger/value",
+        joystick: Some("input/thumbstick"),
+        haptic: "output/haptic",
+    },
+    XrProfilePreset {
+        interaction_profile: HTC_VIVE,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/squeeze/click",
+        trigger: "input/trigger/value",
+        joystick: Some("input/trackpad"),
+        haptic: "output/haptic",
+    },
+    XrProfilePreset {
+        interaction_profile: MICROSOFT_MOTION,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/squeeze/click",
+        trigger: "input/trigger/value",
+        joystick: Some("input/thumbstick"),
+        haptic: "output/haptic",
+    },
+    XrProfilePreset {
+        interaction_profile: PICO_NEO3,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/squeeze/value",
+        trigger: "input/trigger/value",
+        joystick: Some("input/thumbstick"),
+        haptic: "output/haptic",
+    },
+    XrProfilePreset {
+        interaction_profile: PICO4,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/squeeze/value",
+        trigger: "input/trigger/value",
+        joystick: Some("input/thumbstick"),
+        haptic: "output/haptic",
+    },
+    XrProfilePreset {
+        interaction_profile: KHR_SIMPLE,
+        grip_pose: "input/grip/pose",
+        aim_pose: "input/aim/pose",
+        squeeze: "input/select/click",
+        trigger: "input/select/click",
+        joystick: None,
+        haptic: "output/haptic",
+    },
+];
+
+/// Suggested bindings for `input` on every profile in [`PROFILE_PRESETS`],
+/// `user_paths` are the top level paths to bind on like `/user/hand/left`
+pub fn preset_bindings(input: XrPresetInput, user_paths: &[&str]) -> Vec<XrBinding> {
+    PROFILE_PRESETS
+        .iter()
+        .filter_map(|preset| {
+            let component = preset.component(input)?;
+            Some(XrBinding {
+                interaction_profile: preset.interaction_profile.to_string(),
+                binding: user_paths
+                    .iter()
+                    .map(|user_path| format!("{user_path}/{component}"))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// What kind of value a component path produces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XrComponentKind {
+    Click,
+    Touch,
+    Value,
+    Vec2,
+    Pose,
+    Haptic,
+}
+
+/// Every component path (relative to the hand) a profile has on each hand
+#[derive(Clone, Copy, Debug)]
+pub struct XrProfileComponents {
+    pub interaction_profile: &'static str,
+    pub left: &'static [&'static [(&'static str, XrComponentKind)]],
+    pub right: &'static [&'static [(&'static str, XrComponentKind)]],
+}
+
+impl XrProfileComponents {
+    pub fn component(&self, hand: Hand, component: &str) -> Option<XrComponentKind> {
+        let groups = match hand {
+            Hand::Left => self.left,
+            Hand::Right => self.right,
+        };
+        groups
+            .iter()
+            .flat_map(|group| group.iter())
+            .find(|(path, _)| *path == component)
+            .map(|(_, kind)| *kind)
+    }
+}
+
+const POSES_AND_HAPTIC: &[(&str, XrComponentKind)] = &[
+    ("input/grip/pose", Pose),
+    ("input/aim/pose", Pose),
+    ("output/haptic", Haptic),
+];
+const THUMBSTICK: &[(&str, XrComponentKind)] = &[
+    ("input/thumbstick", Vec2),
+    ("input/thumbstick/x", Value),
+    ("input/thumbstick/y", Value),
+    ("input/thumbstick/click", Click),
+];
+const THUMBSTICK_TOUCH: &[(&str, XrComponentKind)] = &[("input/thumbstick/touch", Touch)];
+const TRACKPAD: &[(&str, XrComponentKind)] = &[
+    ("input/trackpad", Vec2),
+    ("input/trackpad/x", Value),
+    ("input/trackpad/y", Value),
+    ("input/trackpad/click", Click),
+    ("input/trackpad/touch", Touch),
+];
+const TRIGGER: &[(&str, XrComponentKind)] = &[("input/trigger/value", Value)];
+const TRIGGER_TOUCH: &[(&str, XrComponentKind)] = &[("input/trigger/touch", Touch)];
+const TRIGGER_CLICK: &[(&str, XrComponentKind)] = &[("input/trigger/click", Click)];
+const SQUEEZE_VALUE: &[(&str, XrComponentKind)] = &[("input/squeeze/value", Value)];
+const SQUEEZE_CLICK: &[(&str, XrComponentKind)] = &[("input/squeeze/click", Click)];
+const MENU: &[(&str, XrComponentKind)] = &[("input/menu/click", Click)];
+const SYSTEM: &[(&str, XrComponentKind)] = &[("input/system/click", Click)];
+const X_Y: &[(&str, XrComponentKind)] = &[
+    ("input/x/click", Click),
+    ("input/x/touch", Touch),
+    ("input/y/click", Click),
+    ("input/y/touch", Touch),
+];
+const A_B: &[(&str, XrComponentKind)] = &[
+    ("input/a/click", Click),
+    ("input/a/touch", Touch),
+    ("input/b/click", Click),
+    ("input/b/touch", Touch),
+];
+const THUMBREST: &[(&str, XrComponentKind)] = &[("input/thumbrest/touch", Touch)];
+
+/// Components of every profile in [`PROFILE_PRESETS`], used to validate bindings
+pub const PROFILE_COMPONENTS: &[XrProfileComponents] = &[
+    XrProfileComponents {
+        interaction_profile: OCULUS_TOUCH,
+        left: &[
+            POSES_AND_HAPTIC,
+            THUMBSTICK,
+            THUMBSTICK_TOUCH,
+            TRIGGER,
+            TRIGGER_TOUCH,
+            SQUEEZE_VALUE,
+            X_Y,
+            MENU,
+            THUMBREST,
+        ],
+        right: &[
+            POSES_AND_HAPTIC,
+            THUMBSTICK,
+            THUMBSTICK_TOUCH,
+            TRIGGER,
+            TRIGGER_TOUCH,
+            SQUEEZE_VALUE,
+            A_B,
+            SYSTEM,
+            THUMBREST,
+        ],
+    },
+    XrProfileComponents {
+        interaction_profile: VALVE_INDEX,
+        left: INDEX,
+        right: INDEX,
+    },
+    XrProfileComponents {
+        interaction_profile: HTC_VIVE,
+        left: VIVE,
+        right: VIVE,
+    },
+    XrProfileComponents {
+        interaction_profile: MICROSOFT_MOTION,
+        left: WMR,
+        right: WMR,
+    },
+    XrProfileComponents {
+        interaction_profile: PICO_NEO3,
+        left: &[
+            POSES_AND_HAPTIC,
+            THUMBSTICK,
+            THUMBSTICK_TOUCH,
+            TRIGGER,
+            TRIGGER_TOUCH,
+            TRIGGER_CLICK,
+            SQUEEZE_VALUE,
+            X_Y,
+            MENU,
+            SYSTEM,
+        ],
+        right: &[
+            POSES_AND_HAPTIC,
+            THUMBSTICK,
+            THUMBSTICK_TOUCH,
+            TRIGGER,
+            TRIGGER_TOUCH,
+            TRIGGER_CLICK,
+            SQUEEZE_VALUE,
+            A_B,
+            MENU,
+            SYSTEM,
+        ],
+    },
+    XrProfileComponents {
+        interaction_profile: PICO4,
+        left: &[
+            POSES_AND_HAPTIC,
+            THUMBSTICK,
+            THUMBSTICK_TOUCH,
+            TRIGGER,
+            TRIGGER_TOUCH,
+            TRIGGER_CLICK,
+            SQUEEZE_VALUE,
+            SQUEEZE_CLICK,
+            X_Y,
+            MENU,
+            THUMBREST,
+        ],
+        right: &[
+            POSES_AND_HAPTIC,
+            THUMBSTICK,
+            THUMBSTICK_TOUCH,
+            TRIGGER,
+            TRIGGER_TOUCH,
+            TRIGGER_CLICK,
+            SQUEEZE_VALUE,
+            SQUEEZE_CLICK,
+            A_B,
+            SYSTEM,
+            THUMBREST,
+        ],
+    },
+    XrProfileComponents {
+        interaction_profile: KHR_SIMPLE,
+        left: SIMPLE,
+        right: SIMPLE,
+    },
+];
+
+const INDEX: &[&[(&str, XrComponentKind)]] = &[
+    POSES_AND_HAPTIC,
+    THUMBSTICK,
+    THUMBSTICK_TOUCH,
+    TRIGGER,
+    TRIGGER_TOUCH,
+    TRIGGER_CLICK,
+    SQUEEZE_VALUE,
+    &[("input/squeeze/force", Value)],
+    A_B,
+    &[("input/system/click", Click), ("input/system/touch", Touch)],
+    &[
+        ("input/trackpad", Vec2),
+        ("input/trackpad/x", Value),
+        ("input/trackpad/y", Value),
+        ("input/trackpad/force", Value),
+        ("input/trackpad/touch", Touch),
+    ],
+];
+const VIVE: &[&[(&str, XrComponentKind)]] = &[
+    POSES_AND_HAPTIC,
+    TRACKPAD,
+    TRIGGER,
+    TRIGGER_CLICK,
+    SQUEEZE_CLICK,
+    MENU,
+    SYSTEM,
+];
+const WMR: &[&[(&str, XrComponentKind)]] = &[
+    POSES_AND_HAPTIC,
+    THUMBSTICK,
+    TRACKPAD,
+    TRIGGER,
+    SQUEEZE_CLICK,
+    MENU,
+];
+const SIMPLE: &[&[(&str, XrComponentKind)]] =
+    &[POSES_AND_HAPTIC, &[("input/select/click", Click)], MENU];
+
+pub fn profile_components(interaction_profile: &str) -> Option<&'static XrProfileComponents> {
+    PROFILE_COMPONENTS
+        .iter()
+        .find(|components| components.interaction_profile == interaction_profile)
+}
+
+/// The extension `interaction_profile` needs enabled on the instance, `None` for core profiles
+pub fn required_extension(interaction_profile: &str) -> Option<&'static str> {
+    match interaction_profile {
+        PICO_NEO3 | PICO4 => Some("XR_BD_controller_interaction"),
+        _ => None,
+    }
+}
+
+/// If the runtime accepts bindings for `interaction_profile`, profiles from extensions that
+/// weren't enabled fail the whole suggestion
+pub fn profile_supported(interaction_profile: &str, instance: &OxrInstance) -> bool {
+    match interaction_profile {
+        PICO_NEO3 | PICO4 => instance.exts().bd_controller_interaction.is_some(),
+        _ => true,
+    }
+}
@@ -0,0 +1,599 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_mod_openxr::{
+    action_binding::{OxrSendActionBindings, OxrSuggestActionBinding},
+    action_set_attaching::OxrAttachActionSet,
+    action_set_syncing::{OxrActionSetSyncSet, OxrSyncActionSet},
+    openxr_session_running,
+    resources::OxrInstance,
+    session::OxrSession,
+};
+use bevy_mod_xr::session::{session_available, XrSessionCreated};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    actions::{
+        suggest_bindings, Hand, XrAction, XrActionType, XrActions, XrActionsAttached,
+        XrRawActionState,
+    },
+    config::{Config, ConfigError, XrActionConfig, XrBinding},
+    interaction_profile::XrInteractionProfiles,
+    profiles::{profile_components, XrComponentKind, PROFILE_COMPONENTS},
+};
+use crate::error::{ReportXrError, XrSubsystem, XrUtilsError};
+
+/// Bindings the player picked for one action on one interaction profile, they replace the ones
+/// from the [`Config`] for that profile
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct XrBindingOverride {
+    pub action: String,
+    pub interaction_profile: String,
+    pub binding: Vec<String>,
+}
+
+/// The player's rebinds, kept in their own file and layered over the game's [`Config`] when the
+/// actions are built. The runtime stops accepting bindings once the actions are attached, so
+/// changes made after that only apply from the next launch and the running bindings stay as they
+/// are, see [`XrActionsStale`](super::actions::XrActionsStale). They only last that long if they
+/// get saved, [`XrRebindPlugin::overrides_path`] does it after every capture.
+///
+/// ```toml
+/// [[overrides]]
+/// action = "squeeze"
+/// interaction_profile = "/interaction_profiles/oculus/touch_controller"
+/// binding = ["/user/hand/right/input/a/click"]
+/// ```
+#[derive(Deserialize, Serialize, Resource, Clone, Debug, Default)]
+pub struct XrBindingOverrides {
+    #[serde(default)]
+    pub overrides: Vec<XrBindingOverride>,
+}
+
+impl XrBindingOverrides {
+    pub fn from_toml_str(string: &str) -> Result<Self, ConfigError> {
+        toml::from_str(string).map_err(|source| ConfigError::Parse { path: None, source })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let string = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&string).map_err(|source| ConfigError::Parse {
+            path: Some(path.to_path_buf()),
+            source,
+        })
+    }
+
+    /// Same as [`XrBindingOverrides::load`] but a missing or broken file means no overrides
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+        match Self::load(path) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                error!("{e}, ignoring the binding overrides");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let string = self.to_toml_string().map_err(ConfigError::Serialize)?;
+        std::fs::write(&path, string).map_err(|source| ConfigError::Io { path, source })
+    }
+
+    pub fn get(&self, action: &str, interaction_profile: &str) -> Option<&XrBindingOverride> {
+        self.overrides
+            .iter()
+            .find(|o| o.action == action && o.interaction_profile == interaction_profile)
+    }
+
+    /// Replaces the bindings of `action` on `interaction_profile`
+    pub fn set(&mut self, action: &str, interaction_profile: &str, binding: Vec<String>) {
+        self.remove(action, interaction_profile);
+        self.overrides.push(XrBindingOverride {
+            action: action.to_string(),
+            interaction_profile: interaction_profile.to_string(),
+            binding,
+        });
+    }
+
+    /// Goes back to the game's bindings for `action` on `interaction_profile`
+    pub fn remove(&mut self, action: &str, interaction_profile: &str) {
+        self.overrides
+            .retain(|o| o.action != action || o.interaction_profile != interaction_profile);
+    }
+}
+
+impl Config {
+    /// This config with `overrides` applied, use it to list the bindings currently in effect
+    pub fn with_overrides(&self, overrides: &XrBindingOverrides) -> Config {
+        let mut config = self.clone();
+        for o in overrides.overrides.iter() {
+            let Some(action) = config.actions.iter_mut().find(|a| a.name == o.action) else {
+                warn!("binding override for unknown action {}", o.action);
+                continue;
+            };
+            match action
+                .bindings
+                .iter_mut()
+                .find(|b| b.interaction_profile == o.interaction_profile)
+            {
+                Some(binding) => binding.binding = o.binding.clone(),
+                None => action.bindings.push(XrBinding {
+                    interaction_profile: o.interaction_profile.clone(),
+                    binding: o.binding.clone(),
+                }),
+            }
+        }
+        config
+    }
+}
+
+/// The [`Config`] the actions are built from, with the overrides if there are any
+pub(crate) fn layered_config<'a>(
+    config: &'a Config,
+    overrides: Option<&XrBindingOverrides>,
+) -> Cow<'a, Config> {
+    match overrides {
+        Some(overrides) if !overrides.overrides.is_empty() => {
+            Cow::Owned(config.with_overrides(overrides))
+        }
+        _ => Cow::Borrowed(config),
+    }
+}
+
+/// Sent when a capture started with [`XrRebindCapture::start`] picked up an input, it's added to
+/// the [`XrBindingOverrides`] and applies from the next launch if they're saved, either by
+/// [`XrRebindPlugin::overrides_path`] or with [`XrBindingOverrides::save`]
+#[derive(Event, Clone, Debug)]
+pub struct XrRebound {
+    pub action: XrAction,
+    pub interaction_profile: String,
+    pub binding: Vec<String>,
+}
+
+/// Waits for the player to press something and binds it to an action, see [`XrRebindPlugin`]
+#[derive(Resource, Default, Debug)]
+pub struct XrRebindCapture {
+    target: Option<XrAction>,
+    /// Inputs already held, they have to be let go before they count
+    held: Option<HashSet<(XrAction, Hand)>>,
+}
+
+impl XrRebindCapture {
+    /// Binds the next input the player presses to `action`, poses and haptics can't be captured
+    pub fn start(&mut self, action: XrAction) {
+        self.target = Some(action);
+        self.held = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.target = None;
+        self.held = None;
+    }
+
+    pub fn capturing(&self) -> Option<&XrAction> {
+        self.target.as_ref()
+    }
+}
+
+/// A second action set with an action for every button, trigger and stick of the known profiles,
+/// only synced while capturing
+#[derive(Resource)]
+pub struct XrCaptureActions {
+    actions: XrActions,
+    config: Config,
+    components: HashMap<XrAction, (&'static str, XrComponentKind)>,
+}
+
+impl XrCaptureActions {
+    fn config() -> (Config, HashMap<XrAction, (&'static str, XrComponentKind)>) {
+        let mut components: Vec<(&'static str, XrComponentKind)> = Vec::new();
+        for profile in PROFILE_COMPONENTS {
+            for (component, kind) in profile
+                .left
+                .iter()
+                .chain(profile.right.iter())
+                .flat_map(|group| group.iter())
+            {
+                let capturable = matches!(
+                    kind,
+                    XrComponentKind::Click | XrComponentKind::Value | XrComponentKind::Vec2
+                );
+                if capturable && !components.iter().any(|(c, _)| c == component) {
+                    components.push((*component, *kind));
+                }
+            }
+        }
+
+        let mut actions = Vec::new();
+        let mut lookup = HashMap::new();
+        for (component, kind) in components {
+            let action_type = match kind {
+                XrComponentKind::Value => XrActionType::Float,
+                XrComponentKind::Vec2 => XrActionType::Vec2,
+                _ => XrActionType::Bool,
+            };
+            let mut bindings = Vec::new();
+            for profile in PROFILE_COMPONENTS {
+                let binding: Vec<String> = Hand::ALL
+                    .into_iter()
+                    .filter(|hand| profile.component(*hand, component).is_some())
+                    .map(|hand| format!("{}/{component}", hand.path()))
+                    .collect();
+                if !binding.is_empty() {
+                    bindings.push(XrBinding {
+                        interaction_profile: profile.interaction_profile.to_string(),
+                        binding,
+                    });
+                }
+            }
+            let action_config = XrActionConfig {
+                name: format!("capture_{}", component.replace('/', "_")),
+                pretty_name: format!("Capture {component}"),
+                action_type,
+                subaction_paths: Hand::ALL.to_vec(),
+                bindings,
+                timing: default(),
+                threshold: default(),
+                joystick: default(),
+                set: None,
+                fallback: Vec::new(),
+            };
+            lookup.insert(action_config.action(), (component, kind));
+            actions.push(action_config);
+        }
+        (
+            Config::new("xr_utils_capture", "Rebind Capture", actions),
+            lookup,
+        )
+    }
+
+    /// Which of the capture inputs are held down right now
+    fn held(&self, session: &OxrSession) -> HashSet<(XrAction, Hand)> {
+        let mut held = HashSet::new();
+        for (action, raw) in self.actions.raw_actions() {
+            for (hand, path) in self.actions.subaction_paths(action) {
+                let down = match raw {
+                    XrRawActionState::Bool(x) => x
+                        .state(session, path)
+                        .is_ok_and(|s| s.is_active && s.current_state),
+                    XrRawActionState::Float(x) => x
+                        .state(session, path)
+                        .is_ok_and(|s| s.is_active && s.current_state > 0.5),
+                    XrRawActionState::Vec2(x) => x.state(session, path).is_ok_and(|s| {
+                        s.is_active
+                            && Vec2::new(s.current_state.x, s.current_state.y).length() > 0.5
+                    }),
+                    _ => false,
+                };
+                if down {
+                    held.insert((action.clone(), hand));
+                }
+            }
+        }
+        held
+    }
+}
+
+/// Adds [`XrRebindCapture`] and the action set it listens with, the set has to exist before the
+/// session starts so add this plugin at startup even if rebinding happens much later
+#[derive(Default)]
+pub struct XrRebindPlugin {
+    /// Where the [`XrBindingOverrides`] are loaded from and saved to after every capture, with
+    /// `None` saving them is up to the game
+    pub overrides_path: Option<PathBuf>,
+}
+
+/// Where captured bindings get saved, from [`XrRebindPlugin::overrides_path`]
+#[derive(Resource)]
+struct XrOverridesPath(PathBuf);
+
+impl Plugin for XrRebindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrRebindCapture>();
+        if let Some(path) = &self.overrides_path {
+            if !app.world().contains_resource::<XrBindingOverrides>() {
+                app.insert_resource(XrBindingOverrides::load_or_default(path));
+            }
+            app.insert_resource(XrOverridesPath(path.clone()));
+        }
+        app.init_resource::<XrBindingOverrides>();
+        app.add_event::<XrRebound>();
+        app.add_systems(Startup, create_capture_actions.run_if(session_available));
+        app.add_systems(OxrSendActionBindings, suggest_capture_bindings);
+        app.add_systems(XrSessionCreated, attach_capture_set);
+        app.add_systems(
+            PreUpdate,
+            sync_capture_set
+                .before(OxrActionSetSyncSet)
+                .run_if(openxr_session_running),
+        );
+        app.add_systems(
+            PreUpdate,
+            capture_binding
+                .after(OxrActionSetSyncSet)
+                .run_if(openxr_session_running),
+        );
+    }
+}
+
+fn create_capture_actions(instance: Res<OxrInstance>, mut cmds: Commands) {
+    let (config, components) = XrCaptureActions::config();
+    match XrActions::from_config(&config, &instance) {
+        Ok(actions) => cmds.insert_resource(XrCaptureActions {
+            actions,
+            config,
+            components,
+        }),
+        Err(e) => cmds.queue(ReportXrError::disable(XrSubsystem::Rebinding, e)),
+    }
+}
+
+fn suggest_capture_bindings(
+    capture: Option<Res<XrCaptureActions>>,
+    attached: Option<Res<XrActionsAttached>>,
+    instance: Res<OxrInstance>,
+    mut bindings: EventWriter<OxrSuggestActionBinding>,
+    mut cmds: Commands,
+) {
+    // a later session reuses the bindings suggested for the first one
+    if attached.is_some() {
+        return;
+    }
+    if let Some(capture) = capture {
+        suggest_bindings(
+            &capture.actions,
+            &capture.config,
+            &instance,
+            &mut bindings,
+            &mut cmds,
+        );
+    }
+}
+
+fn attach_capture_set(
+    capture: Option<Res<XrCaptureActions>>,
+    mut attach: EventWriter<OxrAttachActionSet>,
+) {
+    if let Some(capture) = capture {
+        attach.send(OxrAttachActionSet(capture.actions.set().clone()));
+    }
+}
+
+fn sync_capture_set(
+    capture: Option<Res<XrCaptureActions>>,
+    rebind: Res<XrRebindCapture>,
+    mut sync: EventWriter<OxrSyncActionSet>,
+) {
+    if let (Some(capture), Some(_)) = (capture, rebind.capturing()) {
+        sync.send(OxrSyncActionSet(capture.actions.set().clone()));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn capture_binding(
+    mut rebind: ResMut<XrRebindCapture>,
+    capture: Option<Res<XrCaptureActions>>,
+    session: Option<Res<OxrSession>>,
+    profiles: Res<XrInteractionProfiles>,
+    config: Res<Config>,
+    mut overrides: ResMut<XrBindingOverrides>,
+    path: Option<Res<XrOverridesPath>>,
+    mut rebound: EventWriter<XrRebound>,
+    mut cmds: Commands,
+) {
+    let (Some(target), Some(capture), Some(session)) = (rebind.target.clone(), capture, session)
+    else {
+        return;
+    };
+    let Some(target_config) = config.actions.iter().find(|a| a.name == target.name) else {
+        warn!("can't rebind unknown action {}", target.name);
+        rebind.cancel();
+        return;
+    };
+    let held = capture.held(&session);
+    let Some(previously_held) = rebind.held.replace(held.clone()) else {
+        // first frame, whatever is down already doesn't count
+        return;
+    };
+    let Some((component, hand)) = held
+        .difference(&previously_held)
+        .filter_map(|(action, hand)| Some((capture.components.get(action)?, *hand)))
+        .find(|((_, kind), _)| match target.action_type {
+            XrActionType::Vec2 => *kind == XrComponentKind::Vec2,
+            XrActionType::Float | XrActionType::Bool => {
+                matches!(kind, XrComponentKind::Click | XrComponentKind::Value)
+            }
+            XrActionType::Pose | XrActionType::Haptic => false,
+        })
+        .map(|((component, _), hand)| (*component, hand))
+    else {
+        return;
+    };
+    let Some(interaction_profile) = profiles.get(hand) else {
+        warn!("no interaction profile for {hand:?}, can't rebind");
+        return;
+    };
+
+    // actions used with both hands get the same component on each hand that has it
+    let hands: Vec<Hand> = if target_config.subaction_paths.len() > 1 {
+        let components = profile_components(interaction_profile);
+        target_config
+            .subaction_paths
+            .iter()
+            .copied()
+            .filter(|h| {
+                *h == hand
+                    || components
+                        .is_some_and(|components| components.component(*h, component).is_some())
+            })
+            .collect()
+    } else {
+        vec![hand]
+    };
+    let binding: Vec<String> = hands
+        .iter()
+        .map(|hand| format!("{}/{component}", hand.path()))
+        .collect();
+    info!(
+        "rebound {} to {binding:?} on {interaction_profile}",
+        target.name
+    );
+    overrides.set(&target.name, interaction_profile, binding.clone());
+    if let Some(path) = path {
+        if let Err(e) = overrides.save(&path.0) {
+            cmds.queue(ReportXrError::warn(
+                XrSubsystem::Rebinding,
+                XrUtilsError::Config(e),
+            ));
+        }
+    }
+    rebound.send(XrRebound {
+        action: target,
+        interaction_profile: interaction_profile.to_string(),
+        binding,
+    });
+    rebind.cancel();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{
+        config::DefaultActions,
+        profiles::{OCULUS_TOUCH, VALVE_INDEX},
+        typed::XrTypedAction,
+    };
+
+    fn squeeze_binding<'a>(config: &'a Config, interaction_profile: &str) -> Option<&'a [String]> {
+        config
+            .actions
+            .iter()
+            .find(|action| action.name == "squeeze")?
+            .bindings
+            .iter()
+            .find(|binding| binding.interaction_profile == interaction_profile)
+            .map(|binding| binding.binding.as_slice())
+    }
+
+    #[test]
+    fn overrides_replace_the_profile_binding() {
+        let config = Config::new("main", "Main", vec![DefaultActions::Squeeze.config()]);
+        let mut overrides = XrBindingOverrides::default();
+        overrides.set(
+            "squeeze",
+            OCULUS_TOUCH,
+            vec!["/user/hand/right/input/a/click".to_string()],
+        );
+        let layered = config.with_overrides(&overrides);
+        assert_eq!(
+            squeeze_binding(&layered, OCULUS_TOUCH),
+            Some(&["/user/hand/right/input/a/click".to_string()][..])
+        );
+        // other profiles keep the game's bindings
+        assert_eq!(
+            squeeze_binding(&layered, VALVE_INDEX),
+            squeeze_binding(&config, VALVE_INDEX)
+        );
+        assert_eq!(layered.validate(), Ok(()));
+    }
+
+    #[test]
+    fn overrides_add_missing_profiles_and_skip_unknown_actions() {
+        let mut action = DefaultActions::Squeeze.config();
+        action.bindings.clear();
+        let config = Config::new("main", "Main", vec![action]);
+        let mut overrides = XrBindingOverrides::default();
+        overrides.set(
+            "squeeze",
+            OCULUS_TOUCH,
+            vec!["/user/hand/left/input/x/click".to_string()],
+        );
+        overrides.set("jump", OCULUS_TOUCH, Vec::new());
+        let layered = config.with_overrides(&overrides);
+        assert_eq!(
+            squeeze_binding(&layered, OCULUS_TOUCH),
+            Some(&["/user/hand/left/input/x/click".to_string()][..])
+        );
+        assert_eq!(layered.actions.len(), 1);
+    }
+
+    #[test]
+    fn set_replaces_and_remove_restores() {
+        let mut overrides = XrBindingOverrides::default();
+        overrides.set("squeeze", OCULUS_TOUCH, vec!["a".to_string()]);
+        overrides.set("squeeze", OCULUS_TOUCH, vec!["b".to_string()]);
+        assert_eq!(overrides.overrides.len(), 1);
+        assert_eq!(
+            overrides.get("squeeze", OCULUS_TOUCH).unwrap().binding,
+            vec!["b".to_string()]
+        );
+        overrides.remove("squeeze", OCULUS_TOUCH);
+        assert!(overrides.get("squeeze", OCULUS_TOUCH).is_none());
+        let config = Config::default();
+        assert!(matches!(
+            layered_config(&config, Some(&overrides)),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let mut overrides = XrBindingOverrides::default();
+        overrides.set(
+            "squeeze",
+            OCULUS_TOUCH,
+            vec![
+                "/user/hand/left/input/x/click".to_string(),
+                "/user/hand/right/input/a/click".to_string(),
+            ],
+        );
+        overrides.set("joystick", VALVE_INDEX, Vec::new());
+        let string = overrides.to_toml_string().unwrap();
+        let parsed = XrBindingOverrides::from_toml_str(&string).unwrap();
+        assert_eq!(parsed.overrides, overrides.overrides);
+    }
+
+    #[test]
+    fn parses_the_documented_format() {
+        let overrides = XrBindingOverrides::from_toml_str(
+            r#"
+            [[overrides]]
+            action = "squeeze"
+            interaction_profile = "/interaction_profiles/oculus/touch_controller"
+            binding = ["/user/hand/right/input/a/click"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            overrides.overrides,
+            vec![XrBindingOverride {
+                action: "squeeze".to_string(),
+                interaction_profile: OCULUS_TOUCH.to_string(),
+                binding: vec!["/user/hand/right/input/a/click".to_string()],
+            }]
+        );
+        assert!(XrBindingOverrides::from_toml_str("")
+            .unwrap()
+            .overrides
+            .is_empty());
+    }
+}
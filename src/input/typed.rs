@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+
+use crate::error::XrUtilsError;
+
+use super::{
+    actions::{
+        Hand, XrAction, XrActionStateBool, XrActionStateFloat, XrActionStatePose,
+        XrActionStateVec2, XrActionType, XrInput,
+    },
+    config::{XrActionConfig, XrActionTiming, XrBinding, XrPressThreshold},
+    fallback::XrFallbackBinding,
+    joystick::XrJoystickSettings,
+};
+
+/// Rust type an action reads as, ties a [`XrTypedAction`] to its [`XrActionType`]
+pub trait XrActionValue: 'static {
+    const ACTION_TYPE: XrActionType;
+}
+
+impl XrActionValue for f32 {
+    const ACTION_TYPE: XrActionType = XrActionType::Float;
+}
+
+impl XrActionValue for bool {
+    const ACTION_TYPE: XrActionType = XrActionType::Bool;
+}
+
+impl XrActionValue for Vec2 {
+    const ACTION_TYPE: XrActionType = XrActionType::Vec2;
+}
+
+impl XrActionValue for openxr::Posef {
+    const ACTION_TYPE: XrActionType = XrActionType::Pose;
+}
+
+impl XrActionValue for openxr::Haptic {
+    const ACTION_TYPE: XrActionType = XrActionType::Haptic;
+}
+
+/// An action known at compile time, usually declared with [`xr_actions!`](crate::xr_actions)
+pub trait XrTypedAction: Copy + Send + Sync + 'static {
+    type Value: XrActionValue;
+    const NAME: &'static str;
+    const PRETTY_NAME: &'static str;
+
+    fn subaction_paths(&self) -> Vec<Hand> {
+        Vec::new()
+    }
+
+    fn bindings(&self) -> Vec<XrBinding> {
+        Vec::new()
+    }
+
+    fn fallback(&self) -> Vec<XrFallbackBinding> {
+        Vec::new()
+    }
+
+    fn action(&self) -> XrAction {
+        XrAction {
+            name: Self::NAME.to_string(),
+            pretty_name: Self::PRETTY_NAME.to_string(),
+            action_type: Self::Value::ACTION_TYPE,
+        }
+    }
+
+    fn config(&self) -> XrActionConfig {
+        XrActionConfig {
+            name: Self::NAME.to_string(),
+            pretty_name: Self::PRETTY_NAME.to_string(),
+            action_type: Self::Value::ACTION_TYPE,
+            subaction_paths: self.subaction_paths(),
+            bindings: self.bindings(),
+            timing: XrActionTiming::default(),
+            threshold: XrPressThreshold::default(),
+            joystick: XrJoystickSettings::default(),
+            set: None,
+            fallback: self.fallback(),
+        }
+    }
+}
+
+impl XrInput {
+    pub fn float<A: XrTypedAction<Value = f32>>(&self, action: A) -> Option<&XrActionStateFloat> {
+        self.get(&action.action())?.as_float()
+    }
+
+    /// Float state for `hand`, or the combined one if `hand` is `None`, an error says what's wrong
+    pub fn float_for<A: XrTypedAction<Value = f32>>(
+        &self,
+        action: A,
+        hand: Option<Hand>,
+    ) -> Result<&XrActionStateFloat, XrUtilsError> {
+        self.float_state(&action.action(), hand)
+    }
+
+    pub fn float_hand<A: XrTypedAction<Value = f32>>(
+        &self,
+        action: A,
+        hand: Hand,
+    ) -> Option<&XrActionStateFloat> {
+        self.get_hand(&action.action(), hand)?.as_float()
+    }
+
+    pub fn bool<A: XrTypedAction<Value = bool>>(&self, action: A) -> Option<&XrActionStateBool> {
+        self.get(&action.action())?.as_bool()
+    }
+
+    pub fn bool_hand<A: XrTypedAction<Value = bool>>(
+        &self,
+        action: A,
+        hand: Hand,
+    ) -> Option<&XrActionStateBool> {
+        self.get_hand(&action.action(), hand)?.as_bool()
+    }
+
+    pub fn vec2<A: XrTypedAction<Value = Vec2>>(&self, action: A) -> Option<&XrActionStateVec2> {
+        self.get(&action.action())?.as_vec2()
+    }
+
+    pub fn vec2_hand<A: XrTypedAction<Value = Vec2>>(
+        &self,
+        action: A,
+        hand: Hand,
+    ) -> Option<&XrActionStateVec2> {
+        self.get_hand(&action.action(), hand)?.as_vec2()
+    }
+    pub fn pose<A: XrTypedAction<Value = openxr::Posef>>(
+        &self,
+        action: A,
+    ) -> Option<&XrActionStatePose> {
+        self.get(&action.action())?.as_pose()
+    }
+
+    pub fn pose_hand<A: XrTypedAction<Value = openxr::Posef>>(
+        &self,
+        action: A,
+        hand: Hand,
+    ) -> Option<&XrActionStatePose> {
+        self.get_hand(&action.action(), hand)?.as_pose()
+    }
+}
+
+/// Declares a module of typed actions that reads like an enum, each variant is a unit struct
+/// implementing [`XrTypedAction`] so `input.float(GameActions::Grab)` is checked by the compiler.
+///
+/// ```ignore
+/// xr_actions! {
+///     pub GameActions {
+///         Grab: f32 = "grab", "Grab",
+///             subactions: [Hand::Left, Hand::Right],
+///             bindings: preset_bindings(XrPresetInput::Squeeze, &["/user/hand/left", "/user/hand/right"]);
+///         Menu: bool = "menu", "Open Menu",
+///             fallback: vec![XrFallbackBinding::new(XrFallbackInput::Key(KeyCode::Escape))];
+///     }
+/// }
+///
+/// app.insert_resource(Config::new("game", "Game", GameActions::action_configs()));
+/// ```
+#[macro_export]
+macro_rules! xr_actions {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident : $value:ty = $action_name:literal, $pretty_name:literal
+                $(, subactions: [$($hand:expr),* $(,)?])?
+                $(, bindings: $bindings:expr)?
+                $(, fallback: $fallback:expr)?
+                ;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(non_snake_case)]
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $(
+                $(#[$variant_meta])*
+                #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+                pub struct $variant;
+
+                impl $crate::prelude::XrTypedAction for $variant {
+                    type Value = $value;
+                    const NAME: &'static str = $action_name;
+                    const PRETTY_NAME: &'static str = $pretty_name;
+
+                    $(
+                        fn subaction_paths(&self) -> Vec<$crate::prelude::Hand> {
+                            vec![$($hand),*]
+                        }
+                    )?
+
+                    $(
+                        fn bindings(&self) -> Vec<$crate::prelude::XrBinding> {
+                            $bindings
+                        }
+                    )?
+
+                    $(
+                        fn fallback(&self) -> Vec<$crate::prelude::XrFallbackBinding> {
+                            $fallback
+                        }
+                    )?
+                }
+            )*
+
+            /// Config entries for every action declared here
+            pub fn action_configs() -> Vec<$crate::prelude::XrActionConfig> {
+                vec![$($crate::prelude::XrTypedAction::config(&$variant)),*]
+            }
+        }
+    };
+}
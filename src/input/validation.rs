@@ -0,0 +1,550 @@
+use bevy::{
+    prelude::{Commands, Res},
+    utils::HashSet,
+};
+
+use super::{
+    actions::{Hand, XrActionType},
+    config::Config,
+    profiles::{profile_components, XrComponentKind},
+    rebinding::{layered_config, XrBindingOverrides},
+};
+use crate::error::{ReportXrError, XrSubsystem, XrUtilsError};
+
+/// Longest names the runtime accepts, not counting the null terminator
+const MAX_NAME_LEN: usize = 63;
+const MAX_PRETTY_NAME_LEN: usize = 127;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum XrConfigProblem {
+    /// Names may only use lowercase letters, digits, `-`, `_` and `.`
+    InvalidSetName(String),
+    InvalidActionName(String),
+    InvalidPrettyName(String),
+    DuplicateActionName(String),
+    /// Two actions in the same set share a pretty name
+    DuplicatePrettyName(String),
+    InvalidPath {
+        action: String,
+        path: String,
+    },
+    /// The profile doesn't have that component on that hand
+    UnknownComponent {
+        action: String,
+        interaction_profile: String,
+        path: String,
+    },
+    WrongComponentType {
+        action: String,
+        path: String,
+        action_type: XrActionType,
+    },
+    /// The binding isn't under any of the action's subaction paths
+    NotInSubactionPaths {
+        action: String,
+        path: String,
+    },
+    /// The release threshold is above the press threshold
+    InvalidThreshold(String),
+    /// The inner deadzone isn't below the outer one
+    InvalidDeadzone(String),
+    /// The joystick's direction release is above its direction press
+    InvalidDirectionThreshold(String),
+    DuplicateSetName(String),
+    /// Set pretty names have to be unique across every set
+    DuplicateSetPrettyName(String),
+    /// Pose and haptic actions can't have fallback bindings
+    UnsupportedFallback(String),
+    /// The action names a set that isn't in [`Config::sets`]
+    UnknownSet {
+        action: String,
+        set: String,
+    },
+}
+
+impl std::fmt::Display for XrConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XrConfigProblem::InvalidSetName(name) => write!(
+                f,
+                "action set name {name:?} must be 1-{MAX_NAME_LEN} lowercase letters, digits, '-', '_' or '.'"
+            ),
+            XrConfigProblem::InvalidActionName(name) => write!(
+                f,
+                "action name {name:?} must be 1-{MAX_NAME_LEN} lowercase letters, digits, '-', '_' or '.'"
+            ),
+            XrConfigProblem::InvalidPrettyName(name) => write!(
+                f,
+                "pretty name {name:?} must be 1-{MAX_PRETTY_NAME_LEN} bytes"
+            ),
+            XrConfigProblem::DuplicateActionName(name) => {
+                write!(f, "action name {name:?} is used more than once")
+            }
+            XrConfigProblem::DuplicatePrettyName(name) => {
+                write!(f, "pretty name {name:?} is used more than once in its action set")
+            }
+            XrConfigProblem::InvalidPath { action, path } => {
+                write!(f, "{action}: {path:?} isn't a valid binding path")
+            }
+            XrConfigProblem::UnknownComponent {
+                action,
+                interaction_profile,
+                path,
+            } => write!(f, "{action}: {interaction_profile} has no {path}"),
+            XrConfigProblem::WrongComponentType {
+                action,
+                path,
+                action_type,
+            } => write!(f, "{action}: {path} can't be bound to a {action_type:?} action"),
+            XrConfigProblem::NotInSubactionPaths { action, path } => {
+                write!(f, "{action}: {path} isn't under any of its subaction paths")
+            }
+            XrConfigProblem::InvalidThreshold(action) => {
+                write!(f, "{action}: release threshold is above the press threshold")
+            }
+            XrConfigProblem::InvalidDeadzone(action) => {
+                write!(f, "{action}: inner deadzone has to be below the outer deadzone")
+            }
+            XrConfigProblem::InvalidDirectionThreshold(action) => {
+                write!(f, "{action}: direction release is above the direction press")
+            }
+            XrConfigProblem::UnsupportedFallback(action) => {
+                write!(f, "{action}: only float, bool and vec2 actions can have fallback bindings")
+            }
+            XrConfigProblem::DuplicateSetName(name) => {
+                write!(f, "action set name {name:?} is used more than once")
+            }
+            XrConfigProblem::DuplicateSetPrettyName(name) => {
+                write!(f, "action set pretty name {name:?} is used more than once")
+            }
+            XrConfigProblem::UnknownSet { action, set } => {
+                write!(f, "{action}: there's no action set called {set:?}")
+            }
+        }
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'))
+}
+
+fn is_valid_pretty_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= MAX_PRETTY_NAME_LEN
+}
+
+fn accepts(action_type: &XrActionType, kind: XrComponentKind) -> bool {
+    match action_type {
+        XrActionType::Float | XrActionType::Bool => matches!(
+            kind,
+            XrComponentKind::Click | XrComponentKind::Touch | XrComponentKind::Value
+        ),
+        XrActionType::Vec2 => kind == XrComponentKind::Vec2,
+        XrActionType::Pose => kind == XrComponentKind::Pose,
+        XrActionType::Haptic => kind == XrComponentKind::Haptic,
+    }
+}
+
+/// Splits `/user/hand/left/input/squeeze/value` into the hand and `input/squeeze/value`
+fn split_hand_path(path: &str) -> Option<(Hand, &str)> {
+    Hand::ALL.into_iter().find_map(|hand| {
+        let component = path.strip_prefix(hand.path())?.strip_prefix('/')?;
+        Some((hand, component))
+    })
+}
+
+impl Config {
+    /// Checks the config against the rules the runtime enforces, returns every problem found.
+    /// Bindings for profiles missing from [`PROFILE_COMPONENTS`](super::profiles::PROFILE_COMPONENTS)
+    /// only get their syntax checked.
+    pub fn validate(&self) -> Result<(), Vec<XrConfigProblem>> {
+        let mut problems = Vec::new();
+        if !is_valid_name(&self.set_name) {
+            problems.push(XrConfigProblem::InvalidSetName(self.set_name.clone()));
+        }
+        if !is_valid_pretty_name(&self.set_pretty_name) {
+            problems.push(XrConfigProblem::InvalidPrettyName(
+                self.set_pretty_name.clone(),
+            ));
+        }
+        let mut set_names = HashSet::from([self.set_name.as_str()]);
+        let mut set_pretty_names = HashSet::from([self.set_pretty_name.as_str()]);
+        for set in self.sets.iter() {
+            if !is_valid_name(&set.name) {
+                problems.push(XrConfigProblem::InvalidSetName(set.name.clone()));
+            }
+            if !set_names.insert(set.name.as_str()) {
+                problems.push(XrConfigProblem::DuplicateSetName(set.name.clone()));
+            }
+            if !is_valid_pretty_name(&set.pretty_name) {
+                problems.push(XrConfigProblem::InvalidPrettyName(set.pretty_name.clone()));
+            }
+            if !set_pretty_names.insert(set.pretty_name.as_str()) {
+                problems.push(XrConfigProblem::DuplicateSetPrettyName(
+                    set.pretty_name.clone(),
+                ));
+            }
+        }
+
+        let mut names = HashSet::new();
+        let mut pretty_names = HashSet::new();
+        for action in self.actions.iter() {
+            if !is_valid_name(&action.name) {
+                problems.push(XrConfigProblem::InvalidActionName(action.name.clone()));
+            }
+            if !names.insert(action.name.as_str()) {
+                problems.push(XrConfigProblem::DuplicateActionName(action.name.clone()));
+            }
+            if !is_valid_pretty_name(&action.pretty_name) {
+                problems.push(XrConfigProblem::InvalidPrettyName(
+                    action.pretty_name.clone(),
+                ));
+            }
+            // the runtime only wants them unique within a set
+            let set = action.set.as_deref().unwrap_or(&self.set_name);
+            if !pretty_names.insert((set, action.pretty_name.as_str())) {
+                problems.push(XrConfigProblem::DuplicatePrettyName(
+                    action.pretty_name.clone(),
+                ));
+            }
+            if action.threshold.release > action.threshold.press {
+                problems.push(XrConfigProblem::InvalidThreshold(action.name.clone()));
+            }
+            if action.joystick.inner_deadzone >= action.joystick.outer_deadzone {
+                problems.push(XrConfigProblem::InvalidDeadzone(action.name.clone()));
+            }
+            if action.joystick.direction_release > action.joystick.direction_press {
+                problems.push(XrConfigProblem::InvalidDirectionThreshold(
+                    action.name.clone(),
+                ));
+            }
+            if !action.fallback.is_empty()
+                && matches!(
+                    action.action_type,
+                    XrActionType::Pose | XrActionType::Haptic
+                )
+            {
+                problems.push(XrConfigProblem::UnsupportedFallback(action.name.clone()));
+            }
+            if let Some(set) = &action.set {
+                if *set != self.set_name && !self.sets.iter().any(|s| &s.name == set) {
+                    problems.push(XrConfigProblem::UnknownSet {
+                        action: action.name.clone(),
+                        set: set.clone(),
+                    });
+                }
+            }
+
+            for binding in action.bindings.iter() {
+                let components = profile_components(&binding.interaction_profile);
+                for path in binding.binding.iter() {
+                    if !path.starts_with("/user/") || path.ends_with('/') || path.contains("//") {
+                        problems.push(XrConfigProblem::InvalidPath {
+                            action: action.name.clone(),
+                            path: path.clone(),
+                        });
+                        continue;
+                    }
+                    let hand_component = split_hand_path(path);
+                    if !action.subaction_paths.is_empty()
+                        && !hand_component
+                            .is_some_and(|(hand, _)| action.subaction_paths.contains(&hand))
+                    {
+                        problems.push(XrConfigProblem::NotInSubactionPaths {
+                            action: action.name.clone(),
+                            path: path.clone(),
+                        });
+                    }
+                    let Some(components) = components else {
+                        continue;
+                    };
+                    let kind = hand_component
+                        .and_then(|(hand, component)| components.component(hand, component));
+                    match kind {
+                        None => problems.push(XrConfigProblem::UnknownComponent {
+                            action: action.name.clone(),
+                            interaction_profile: binding.interaction_profile.clone(),
+                            path: path.clone(),
+                        }),
+                        Some(kind) if !accepts(&action.action_type, kind) => {
+                            problems.push(XrConfigProblem::WrongComponentType {
+                                action: action.name.clone(),
+                                path: path.clone(),
+                                action_type: action.action_type.clone(),
+                            })
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+/// Reports an invalid [`Config`] at startup whatever backend fills the input, the mock,
+/// fallback and simulator would otherwise run with actions a headset refuses. Disabling
+/// [`XrSubsystem::Actions`] keeps the OpenXR backend from reporting it again.
+pub fn validate_config(
+    config: Res<Config>,
+    overrides: Option<Res<XrBindingOverrides>>,
+    mut cmds: Commands,
+) {
+    let config = layered_config(&config, overrides.as_deref());
+    if let Err(problems) = config.validate() {
+        cmds.queue(ReportXrError::disable(
+            XrSubsystem::Actions,
+            XrUtilsError::InvalidConfig(problems),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{
+        error::{XrDisabledSubsystems, XrUtilsErrorEvent},
+        input::{
+            config::{DefaultActions, XrActionConfig, XrActionSetConfig, XrBinding},
+            fallback::{XrFallbackBinding, XrFallbackInput},
+            profiles::OCULUS_TOUCH,
+            typed::XrTypedAction,
+        },
+        mock::mock_app,
+    };
+
+    fn squeeze() -> XrActionConfig {
+        DefaultActions::Squeeze.config()
+    }
+
+    fn problems(config: &Config) -> Vec<XrConfigProblem> {
+        config.validate().err().unwrap_or_default()
+    }
+
+    fn bound_to(mut action: XrActionConfig, path: &str) -> XrActionConfig {
+        action.bindings = vec![XrBinding {
+            interaction_profile: OCULUS_TOUCH.to_string(),
+            binding: vec![path.to_string()],
+        }];
+        action
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(Config::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_names() {
+        let mut action = squeeze();
+        action.name = "Squeeze Action".to_string();
+        action.pretty_name = String::new();
+        let mut config = Config::new("Main Set", "Main", vec![action]);
+        config.sets.push(XrActionSetConfig {
+            name: "x".repeat(MAX_NAME_LEN + 1),
+            pretty_name: "Menu".to_string(),
+            priority: 0,
+        });
+        let problems = problems(&config);
+        assert!(problems.contains(&XrConfigProblem::InvalidSetName("Main Set".to_string())));
+        assert!(problems.contains(&XrConfigProblem::InvalidSetName(
+            "x".repeat(MAX_NAME_LEN + 1)
+        )));
+        assert!(problems.contains(&XrConfigProblem::InvalidActionName(
+            "Squeeze Action".to_string()
+        )));
+        assert!(problems.contains(&XrConfigProblem::InvalidPrettyName(String::new())));
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let mut other = squeeze();
+        other.name = "grip".to_string();
+        let config = Config::new("main", "Main", vec![squeeze(), squeeze(), other]);
+        let problems = problems(&config);
+        assert!(problems.contains(&XrConfigProblem::DuplicateActionName("squeeze".to_string())));
+        assert!(problems.contains(&XrConfigProblem::DuplicatePrettyName(
+            "Hand Squeeze".to_string()
+        )));
+        assert!(!problems.contains(&XrConfigProblem::DuplicateActionName("grip".to_string())));
+    }
+
+    #[test]
+    fn pretty_names_only_clash_within_a_set() {
+        let mut menu_squeeze = squeeze();
+        menu_squeeze.name = "menu_squeeze".to_string();
+        menu_squeeze.set = Some("menu".to_string());
+        let mut config = Config::new("main", "Main", vec![squeeze(), menu_squeeze]);
+        config.sets.push(XrActionSetConfig {
+            name: "menu".to_string(),
+            pretty_name: "Menu".to_string(),
+            priority: 0,
+        });
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn duplicate_set_pretty_name() {
+        let mut config = Config::new("main", "Main", vec![squeeze()]);
+        for name in ["menu", "pause"] {
+            config.sets.push(XrActionSetConfig {
+                name: name.to_string(),
+                pretty_name: "Main".to_string(),
+                priority: 0,
+            });
+        }
+        assert_eq!(
+            problems(&config),
+            vec![
+                XrConfigProblem::DuplicateSetPrettyName("Main".to_string()),
+                XrConfigProblem::DuplicateSetPrettyName("Main".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_set_name() {
+        let mut config = Config::new("main", "Main", vec![squeeze()]);
+        for pretty_name in ["Menu", "Other Menu"] {
+            config.sets.push(XrActionSetConfig {
+                name: "menu".to_string(),
+                pretty_name: pretty_name.to_string(),
+                priority: 0,
+            });
+        }
+        config.sets.push(XrActionSetConfig {
+            name: "main".to_string(),
+            pretty_name: "Main Again".to_string(),
+            priority: 0,
+        });
+        let problems = problems(&config);
+        assert!(problems.contains(&XrConfigProblem::DuplicateSetName("menu".to_string())));
+        assert!(problems.contains(&XrConfigProblem::DuplicateSetName("main".to_string())));
+    }
+
+    #[test]
+    fn unknown_component() {
+        // the left touch controller has x and y, not a and b
+        let action = bound_to(squeeze(), "/user/hand/left/input/a/click");
+        let config = Config::new("main", "Main", vec![action]);
+        assert_eq!(
+            problems(&config),
+            vec![XrConfigProblem::UnknownComponent {
+                action: "squeeze".to_string(),
+                interaction_profile: OCULUS_TOUCH.to_string(),
+                path: "/user/hand/left/input/a/click".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn wrong_component_kind() {
+        let action = bound_to(squeeze(), "/user/hand/left/input/thumbstick");
+        let config = Config::new("main", "Main", vec![action]);
+        assert_eq!(
+            problems(&config),
+            vec![XrConfigProblem::WrongComponentType {
+                action: "squeeze".to_string(),
+                path: "/user/hand/left/input/thumbstick".to_string(),
+                action_type: XrActionType::Float,
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_set() {
+        let mut action = squeeze();
+        action.set = Some("menu".to_string());
+        let mut main = DefaultActions::Joystick.config();
+        main.set = Some("main".to_string());
+        let config = Config::new("main", "Main", vec![action, main]);
+        assert_eq!(
+            problems(&config),
+            vec![XrConfigProblem::UnknownSet {
+                action: "squeeze".to_string(),
+                set: "menu".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn unsupported_fallback() {
+        let fallback = vec![XrFallbackBinding::new(XrFallbackInput::Key(KeyCode::Space))];
+        let mut pose = DefaultActions::Pose.config();
+        pose.fallback = fallback.clone();
+        let mut haptic = DefaultActions::Haptic.config();
+        haptic.fallback = fallback.clone();
+        let mut action = squeeze();
+        action.fallback = fallback;
+        let config = Config::new("main", "Main", vec![pose, haptic, action]);
+        assert_eq!(
+            problems(&config),
+            vec![
+                XrConfigProblem::UnsupportedFallback("pose".to_string()),
+                XrConfigProblem::UnsupportedFallback("haptic".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_threshold() {
+        let mut action = squeeze();
+        action.threshold.press = 0.3;
+        action.threshold.release = 0.5;
+        let config = Config::new("main", "Main", vec![action]);
+        assert_eq!(
+            problems(&config),
+            vec![XrConfigProblem::InvalidThreshold("squeeze".to_string())]
+        );
+    }
+
+    #[test]
+    fn invalid_deadzone() {
+        let mut action = DefaultActions::Joystick.config();
+        action.joystick.inner_deadzone = 0.5;
+        action.joystick.outer_deadzone = 0.5;
+        let config = Config::new("main", "Main", vec![action]);
+        assert_eq!(
+            problems(&config),
+            vec![XrConfigProblem::InvalidDeadzone("joystick".to_string())]
+        );
+    }
+
+    #[test]
+    fn invalid_direction_threshold() {
+        let mut action = DefaultActions::Joystick.config();
+        action.joystick.direction_press = 0.3;
+        action.joystick.direction_release = 0.6;
+        let config = Config::new("main", "Main", vec![action]);
+        assert_eq!(
+            problems(&config),
+            vec![XrConfigProblem::InvalidDirectionThreshold(
+                "joystick".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_config_is_reported_without_openxr() {
+        let mut app = mock_app(default());
+        let mut config = Config::default();
+        config.actions[0].name = "Bad Name".to_string();
+        app.insert_resource(config);
+        app.update();
+
+        let disabled = app.world().resource::<XrDisabledSubsystems>();
+        assert!(disabled.is_disabled(XrSubsystem::Actions));
+        let events = app.world().resource::<Events<XrUtilsErrorEvent>>();
+        assert_eq!(events.len(), 1);
+    }
+}
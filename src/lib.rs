@@ -1,55 +1,111 @@
+// bevy queries trip it all the time
+#![allow(clippy::type_complexity)]
+
+use std::time::Duration;
+
 use bevy::prelude::*;
-use input::{actions::XrActionType, OpenXRPlugin};
-use prelude::{handle_transform_events, SnapToPosition, SnapToRotation, XrAction};
+use input::{haptics::XrHaptic, OpenXRPlugin};
+use prelude::{
+    handle_transform_events, subsystem_enabled, DefaultActions, SnapToPosition, SnapToRotation,
+    XrAction, XrDisabledSubsystems, XrSubsystem, XrTypedAction, XrUtilsErrorEvent,
+};
 
+mod error;
 mod grab;
 mod gravity_grab;
 mod input;
+mod mock;
 mod movement;
+mod replay;
+mod simulator;
 
 pub mod prelude {
+    pub use crate::error::{
+        subsystem_enabled, XrDisabledSubsystems, XrSubsystem, XrUtilsError, XrUtilsErrorEvent,
+    };
     pub use crate::grab::{Grabbable, Holding};
     pub use crate::gravity_grab::GravityGrabbing;
+    pub use crate::input::action_sets::XrActiveActionSets;
     pub use crate::input::actions::{
-        HeadXRSpace, XrAction, XrActionType, XrInput, XrTrackedLocalFloor, XrTrackedSpace,
-        XrTrackedStage, XrTrackedView,
+        Hand, HeadXRSpace, XrAction, XrActionState, XrActionStateBool, XrActionStateFloat,
+        XrActionStatePose, XrActionStateVec2, XrActionType, XrActionsAttached, XrActionsStale,
+        XrHead, XrInput, XrPressTimer, XrTrackedLocalFloor, XrTrackedSpace, XrTrackedStage,
+        XrTrackedView,
+    };
+    pub use crate::input::button_input::{XrAxis, XrAxisComponent, XrButton};
+    pub use crate::input::config::{
+        Config, ConfigError, DefaultActions, XrActionConfig, XrActionConfigHandle,
+        XrActionSetConfig, XrActionTiming, XrBinding, XrPressThreshold,
+    };
+    pub use crate::input::contexts::{XrInputContextSet, XrInputContexts};
+    pub use crate::input::events::XrActionEvent;
+    pub use crate::input::fallback::{
+        XrFallbackBinding, XrFallbackInput, XrFallbackPlugin, XrFallbackSet,
+    };
+    pub use crate::input::fixed::{XrFixedActionState, XrFixedInput};
+    pub use crate::input::haptics::{
+        XrHaptic, XrHapticEasing, XrHapticKeyframe, XrHapticPattern, XrHapticPlayers,
+        XrHapticPulse, XrPlayHapticPattern, XrStopHapticPattern, XrStopHaptics,
+    };
+    pub use crate::input::interaction_profile::{
+        XrBoundSource, XrBoundSources, XrInteractionProfileChanged, XrInteractionProfiles,
+    };
+    pub use crate::input::joystick::{XrJoystickSettings, XrStickDirection};
+    pub use crate::input::profiles::{
+        preset_bindings, profile_components, XrComponentKind, XrPresetInput, XrProfileComponents,
+        XrProfilePreset,
+    };
+    pub use crate::input::rebinding::{
+        XrBindingOverride, XrBindingOverrides, XrCaptureActions, XrRebindCapture, XrRebindPlugin,
+        XrRebound,
+    };
+    pub use crate::input::typed::{XrActionValue, XrTypedAction};
+    pub use crate::input::validation::XrConfigProblem;
+    pub use crate::mock::{
+        MockXrPlugin, XrMockFrame, XrMockInput, XrMockScript, XrMockSet, XrMockSpace, XrMockValue,
     };
     pub use crate::movement::*;
-    pub use crate::XrUtilsPlugin;
+    pub use crate::replay::{XrRecorder, XrRecorderPlugin, XrRecording, XrRecordingError};
+    pub use crate::simulator::{XrSimulator, XrSimulatorKeys, XrSimulatorPlugin, XrSimulatorSet};
+    pub use crate::xr_actions;
+    pub use crate::{XrUtilsConfig, XrUtilsPlugin};
 }
 
+/// Settings of grab and gravity grab, insert your own before adding the [`XrUtilsPlugin`] to
+/// change them
 #[derive(Resource)]
 pub struct XrUtilsConfig {
-    /// Only supports floats or bools also needs a pose
-    gravity_grab_action_names: Vec<(XrAction, XrAction)>,
-    /// Only supports floats or bools also needs a pose
-    grab_action_names: Vec<(XrAction, XrAction)>,
+    /// Float action read for the [`Hand`](prelude::Hand) of the tracked space to grab with,
+    /// [`DefaultActions::Squeeze`](prelude::DefaultActions) by default. Grab and gravity grab turn
+    /// themselves off if the [`Config`](prelude::Config) doesn't have it.
+    pub grab_action: XrAction,
+    /// Played on the hand that grabs something, `None` to disable
+    pub grab_haptic: Option<XrHaptic>,
+    /// Played on the hand that lets go of something, `None` to disable
+    pub release_haptic: Option<XrHaptic>,
+    /// Played on the hand that launches a gravity grabbed object, `None` to disable
+    pub launch_haptic: Option<XrHaptic>,
 }
 
 impl Default for XrUtilsConfig {
     fn default() -> Self {
         Self {
-            gravity_grab_action_names: vec![
-                (
-                    XrAction::from_string(&"right_squeeze".to_string(), &XrActionType::Float),
-                    XrAction::from_string(&"right_pose".to_string(), &XrActionType::Pose),
-                ),
-                (
-                    XrAction::from_string(&"left_squeeze".to_string(), &XrActionType::Float),
-                    XrAction::from_string(&"left_pose".to_string(), &XrActionType::Pose),
-                ), // ("left_squeeze".to_string(), XrActionType::Float),
-            ],
-            grab_action_names: vec![
-                (
-                    XrAction::from_string(&"right_squeeze".to_string(), &XrActionType::Float),
-                    XrAction::from_string(&"right_pose".to_string(), &XrActionType::Pose),
-                ),
-                (
-                    XrAction::from_string(&"left_squeeze".to_string(), &XrActionType::Float),
-                    XrAction::from_string(&"left_pose".to_string(), &XrActionType::Pose),
-                ),
-                // ("left_squeeze".to_string(), XrActionType::Float),
-            ],
+            grab_action: DefaultActions::Squeeze.action(),
+            grab_haptic: Some(XrHaptic {
+                amplitude: 0.5,
+                frequency: 0.0,
+                duration: Duration::from_millis(40),
+            }),
+            release_haptic: Some(XrHaptic {
+                amplitude: 0.25,
+                frequency: 0.0,
+                duration: Duration::from_millis(20),
+            }),
+            launch_haptic: Some(XrHaptic {
+                amplitude: 0.8,
+                frequency: 0.0,
+                duration: Duration::from_millis(80),
+            }),
         }
     }
 }
@@ -57,14 +113,25 @@ impl Default for XrUtilsConfig {
 pub struct XrUtilsPlugin;
 impl Plugin for XrUtilsPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.insert_resource(XrUtilsConfig::default());
+        app.init_resource::<XrUtilsConfig>();
+        app.add_event::<XrUtilsErrorEvent>();
+        app.init_resource::<XrDisabledSubsystems>();
         app.add_event::<SnapToRotation>();
         app.add_event::<SnapToPosition>();
         app.add_systems(PostUpdate, handle_transform_events);
         app.add_plugins(OpenXRPlugin);
         app.add_systems(Update, gravity_grab::outlines.before(gravity_grab::gesture));
-        app.add_systems(Update, gravity_grab::gesture);
-        app.add_systems(Update, gravity_grab::gravity_grabbing);
-        app.add_systems(Update, grab::grab);
+        app.add_systems(
+            Update,
+            gravity_grab::gesture.run_if(subsystem_enabled(XrSubsystem::GravityGrab)),
+        );
+        app.add_systems(
+            Update,
+            gravity_grab::gravity_grabbing.run_if(subsystem_enabled(XrSubsystem::GravityGrab)),
+        );
+        app.add_systems(
+            Update,
+            grab::grab.run_if(subsystem_enabled(XrSubsystem::Grab)),
+        );
     }
 }
@@ -0,0 +1,401 @@
+use bevy::prelude::*;
+use bevy_mod_openxr::resources::OxrViews;
+use bevy_mod_xr::{session::XrTrackingRoot, spaces::XrVelocity};
+use serde::{Deserialize, Serialize};
+
+use crate::input::{
+    actions::{
+        update_pose_world, update_spaces, Hand, XrAction, XrActionState, XrHead, XrInput, XrSpace,
+    },
+    config::Config,
+};
+
+/// A value to feed into an action
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum XrMockValue {
+    Float(f32),
+    Bool(bool),
+    Vec2(Vec2),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct XrMockInput {
+    pub action: XrAction,
+    /// `None` sets the combined state
+    pub hand: Option<Hand>,
+    pub value: XrMockValue,
+}
+
+/// Where a pose action's space is, reference space relative like the real ones
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct XrMockSpace {
+    pub action: XrAction,
+    pub hand: Option<Hand>,
+    pub transform: Transform,
+    pub linear_velocity: Vec3,
+    pub angular_velocity: Vec3,
+}
+
+/// Everything the mock backend reports for one frame
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct XrMockFrame {
+    pub head: Transform,
+    pub head_linear_velocity: Vec3,
+    pub head_angular_velocity: Vec3,
+    pub spaces: Vec<XrMockSpace>,
+    pub inputs: Vec<XrMockInput>,
+}
+
+/// Frames played back one per update, the last one is held once the script runs out
+#[derive(Resource, Clone, Debug, Default)]
+pub struct XrMockScript {
+    pub frames: Vec<XrMockFrame>,
+    pub frame: usize,
+}
+
+impl XrMockScript {
+    pub fn new(frames: Vec<XrMockFrame>) -> Self {
+        Self { frames, frame: 0 }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.frame >= self.frames.len()
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub struct XrMockSet;
+
+/// Stands in for a runtime so the crate's systems can run in tests. Fills [`XrInput`], the head,
+/// the pose spaces and [`OxrViews`] from the [`XrMockScript`]. Add it next to
+/// [`XrUtilsPlugin`](crate::XrUtilsPlugin) instead of the OpenXR plugins.
+pub struct MockXrPlugin;
+
+impl Plugin for MockXrPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrMockScript>();
+        app.init_resource::<OxrViews>();
+        app.configure_sets(
+            PreUpdate,
+            XrMockSet.before(update_spaces).before(update_pose_world),
+        );
+        app.add_systems(Startup, setup_mock);
+        app.add_systems(PreUpdate, apply_mock_frame.in_set(XrMockSet));
+        app.add_systems(PostUpdate, end_mock_frame);
+    }
+}
+
+fn setup_mock(
+    config: Res<Config>,
+    root_query: Query<(), With<XrTrackingRoot>>,
+    mut cmds: Commands,
+) {
+    cmds.insert_resource(XrInput::from_config(&config));
+    if root_query.is_empty() {
+        cmds.spawn((Transform::default(), Visibility::default(), XrTrackingRoot));
+    }
+    cmds.spawn((
+        Transform::default(),
+        Visibility::default(),
+        XrVelocity::new(),
+        XrHead,
+    ));
+}
+
+pub(crate) fn apply_mock_frame(
+    script: Res<XrMockScript>,
+    inputs: Option<ResMut<XrInput>>,
+    mut views: ResMut<OxrViews>,
+    mut head_query: Query<(&mut Transform, &mut XrVelocity), (With<XrHead>, Without<XrSpace>)>,
+    mut space_query: Query<
+        (&mut Transform, &mut XrVelocity, &XrAction, Option<&Hand>),
+        (With<XrSpace>, Without<XrHead>),
+    >,
+    mut cmds: Commands,
+) {
+    let Some(frame) = script
+        .frames
+        .get(script.frame)
+        .or_else(|| script.frames.last())
+    else {
+        return;
+    };
+    let Some(mut inputs) = inputs else {
+        return;
+    };
+    apply_frame(
+        frame,
+        &mut inputs,
+        &mut views,
+        &mut head_query,
+        &mut space_query,
+        &mut cmds,
+    );
+}
+
+/// Writes `frame` into the same resources and components the real backend fills
+pub(crate) fn apply_frame(
+    frame: &XrMockFrame,
+    inputs: &mut XrInput,
+    views: &mut OxrViews,
+    head_query: &mut Query<(&mut Transform, &mut XrVelocity), (With<XrHead>, Without<XrSpace>)>,
+    space_query: &mut Query<
+        (&mut Transform, &mut XrVelocity, &XrAction, Option<&Hand>),
+        (With<XrSpace>, Without<XrHead>),
+    >,
+    cmds: &mut Commands,
+) {
+    // what the frame leaves out is at rest, like an input nobody touches
+    let in_frame = |action: &XrAction, hand: Option<Hand>| {
+        frame
+            .inputs
+            .iter()
+            .any(|input| input.action == *action && input.hand == hand)
+    };
+    for (action, state) in inputs.state.iter_mut() {
+        if !in_frame(action, None) {
+            state.release();
+        }
+    }
+    for ((action, hand), state) in inputs.hands.iter_mut() {
+        if !in_frame(action, Some(*hand)) {
+            state.release();
+        }
+    }
+    for input in frame.inputs.iter() {
+        let Some(state) = inputs.get_mut(&input.action, input.hand) else {
+            warn!("mock input for unknown action {}", input.action.name);
+            continue;
+        };
+        match (state, input.value) {
+            (XrActionState::Float(state), XrMockValue::Float(value)) => state.update(value),
+            (XrActionState::Bool(state), XrMockValue::Bool(value)) => state.update(value),
+            (XrActionState::Vec2(state), XrMockValue::Vec2(value)) => state.update(value),
+            _ => warn!("mock input for {} has the wrong type", input.action.name),
+        }
+    }
+
+    for (mut transform, mut velocity) in head_query.iter_mut() {
+        *transform = frame.head;
+        velocity.linear = frame.head_linear_velocity;
+        velocity.angular = frame.head_angular_velocity;
+    }
+    views.0 = vec![openxr::View {
+        pose: openxr::Posef {
+            orientation: openxr::Quaternionf {
+                x: frame.head.rotation.x,
+                y: frame.head.rotation.y,
+                z: frame.head.rotation.z,
+                w: frame.head.rotation.w,
+            },
+            position: openxr::Vector3f {
+                x: frame.head.translation.x,
+                y: frame.head.translation.y,
+                z: frame.head.translation.z,
+            },
+        },
+        fov: openxr::Fovf {
+            angle_left: -0.8,
+            angle_right: 0.8,
+            angle_up: 0.8,
+            angle_down: -0.8,
+        },
+    }];
+
+    for space in frame.spaces.iter() {
+        if let Some(XrActionState::Pose(state)) = inputs.get_mut(&space.action, space.hand) {
+            state.is_active = true;
+            state.position_valid = true;
+            state.orientation_valid = true;
+            state.position_tracked = true;
+            state.orientation_tracked = true;
+            state.local = space.transform;
+        }
+        let mut found = false;
+        for (mut transform, mut velocity, action, hand) in space_query.iter_mut() {
+            if *action == space.action && hand.copied() == space.hand {
+                *transform = space.transform;
+                velocity.linear = space.linear_velocity;
+                velocity.angular = space.angular_velocity;
+                found = true;
+            }
+        }
+        if !found {
+            let mut velocity = XrVelocity::new();
+            velocity.linear = space.linear_velocity;
+            velocity.angular = space.angular_velocity;
+            let mut entity = cmds.spawn((space.transform, velocity, XrSpace, space.action.clone()));
+            if let Some(hand) = space.hand {
+                entity.insert(hand);
+            }
+        }
+    }
+}
+
+fn end_mock_frame(mut script: ResMut<XrMockScript>) {
+    if !script.finished() {
+        script.frame += 1;
+    }
+}
+
+/// The crate's plugins with physics but no backend, for tests
+#[cfg(test)]
+pub(crate) fn test_app() -> App {
+    use bevy_rapier3d::prelude::{NoUserData, RapierPhysicsPlugin};
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        TransformPlugin,
+        HierarchyPlugin,
+        bevy::scene::ScenePlugin,
+    ));
+    app.init_asset::<Mesh>();
+    app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default());
+    app.add_plugins(crate::XrUtilsPlugin);
+    app
+}
+
+/// A tracked `hand` at the origin with a sensor the grab systems can use, for tests
+#[cfg(test)]
+pub(crate) fn spawn_hand(app: &mut App, hand: Hand) -> Entity {
+    use bevy_rapier3d::prelude::{Collider, Sensor};
+
+    use crate::prelude::{DefaultActions, XrTrackedSpace, XrTypedAction};
+
+    app.world_mut()
+        .spawn((
+            Transform::IDENTITY,
+            XrVelocity::new(),
+            XrTrackedSpace,
+            DefaultActions::Pose.action(),
+            hand,
+            Collider::ball(0.1),
+            Sensor,
+        ))
+        .id()
+}
+
+/// A weightless cube that can be grabbed, for tests
+#[cfg(test)]
+pub(crate) fn spawn_grabbable(app: &mut App, translation: Vec3) -> Entity {
+    use bevy::render::primitives::Aabb;
+    use bevy_rapier3d::prelude::{Collider, GravityScale, RigidBody, Velocity};
+
+    use crate::prelude::Grabbable;
+
+    app.world_mut()
+        .spawn((
+            Transform::from_translation(translation),
+            RigidBody::Dynamic,
+            Collider::cuboid(0.05, 0.05, 0.05),
+            GravityScale(0.0),
+            Velocity::default(),
+            Aabb::from_min_max(Vec3::splat(-0.05), Vec3::splat(0.05)),
+            Grabbable,
+        ))
+        .id()
+}
+
+/// [`test_app`] on the mock backend
+#[cfg(test)]
+pub(crate) fn mock_app(script: XrMockScript) -> App {
+    let mut app = test_app();
+    app.add_plugins(MockXrPlugin);
+    app.insert_resource(script);
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{DefaultActions, GravityGrabbing, Holding, XrTypedAction};
+
+    /// Frames at rest, then the left squeeze held, then at rest again
+    fn squeeze_script(rest: usize, held: usize) -> XrMockScript {
+        let space = XrMockSpace {
+            action: DefaultActions::Pose.action(),
+            hand: Some(Hand::Left),
+            transform: Transform::IDENTITY,
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+        };
+        let frame = |squeeze: Option<f32>| XrMockFrame {
+            spaces: vec![space.clone()],
+            inputs: squeeze
+                .into_iter()
+                .flat_map(|value| {
+                    [None, Some(Hand::Left)].map(|hand| XrMockInput {
+                        action: DefaultActions::Squeeze.action(),
+                        hand,
+                        value: XrMockValue::Float(value),
+                    })
+                })
+                .collect(),
+            ..default()
+        };
+        let frames = std::iter::repeat_n(frame(None), rest)
+            .chain(std::iter::repeat_n(frame(Some(1.0)), held))
+            .chain([frame(None)])
+            .collect();
+        XrMockScript::new(frames)
+    }
+
+    fn app(script: XrMockScript) -> (App, Entity) {
+        let mut app = mock_app(script);
+        let hand = spawn_hand(&mut app, Hand::Left);
+        (app, hand)
+    }
+
+    #[test]
+    fn squeeze_grabs_and_releases() {
+        let (mut app, hand) = app(squeeze_script(5, 3));
+        let object = spawn_grabbable(&mut app, Vec3::ZERO);
+        for _ in 0..5 {
+            app.update();
+        }
+        assert!(app.world().get::<Holding>(object).is_none());
+        for _ in 0..3 {
+            app.update();
+            let holding = app.world().get::<Holding>(object);
+            assert_eq!(holding.map(|holding| holding.0), Some(hand));
+        }
+        app.update();
+        assert!(app.world().get::<Holding>(object).is_none());
+    }
+
+    #[test]
+    fn squeeze_gravity_grabs_and_lets_go() {
+        let (mut app, hand) = app(squeeze_script(5, 3));
+        // straight below the hand, out of reach of a normal grab
+        let object = spawn_grabbable(&mut app, Vec3::new(0.0, -1.0, 0.0));
+        for _ in 0..5 {
+            app.update();
+        }
+        assert!(app.world().get::<GravityGrabbing>(object).is_none());
+        for _ in 0..3 {
+            app.update();
+            let grabbing = app.world().get::<GravityGrabbing>(object);
+            assert_eq!(grabbing.map(|grabbing| grabbing.0), Some(hand));
+            assert!(app.world().get::<Holding>(object).is_none());
+        }
+        app.update();
+        assert!(app.world().get::<GravityGrabbing>(object).is_none());
+    }
+
+    #[test]
+    fn missing_inputs_are_released() {
+        let (mut app, _) = app(squeeze_script(0, 1));
+        app.update();
+        let input = app.world().resource::<XrInput>();
+        assert!(input.float(DefaultActions::Squeeze).unwrap().is_pressed);
+        app.update();
+        let input = app.world().resource::<XrInput>();
+        let squeeze = input
+            .float_hand(DefaultActions::Squeeze, Hand::Left)
+            .unwrap();
+        assert!(!squeeze.is_pressed);
+        assert_eq!(squeeze.cur_val, 0.0);
+    }
+}
@@ -0,0 +1,623 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{asset::ron, prelude::*};
+use bevy_mod_xr::spaces::XrVelocity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::actions::{
+        end_frame_input, Hand, XrAction, XrActionState, XrActionType, XrHead, XrInput, XrSpace,
+    },
+    mock::{XrMockFrame, XrMockInput, XrMockScript, XrMockSpace, XrMockValue},
+};
+
+const MAGIC: &[u8; 5] = b"XRREC";
+const VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum XrRecordingError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The binary file is truncated or wasn't written by [`XrRecording::to_bytes`]
+    Corrupt(String),
+    /// More actions, spaces, inputs or characters than the binary format has room for
+    TooLarge(String),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for XrRecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XrRecordingError::Io { path, source } => {
+                write!(f, "couldn't access recording {}: {source}", path.display())
+            }
+            XrRecordingError::Corrupt(e) => write!(f, "corrupt recording: {e}"),
+            XrRecordingError::TooLarge(e) => write!(f, "recording too large: {e}"),
+            XrRecordingError::Serialize(e) => write!(f, "couldn't serialize recording: {e}"),
+            XrRecordingError::Parse(e) => write!(f, "malformed recording: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for XrRecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XrRecordingError::Io { source, .. } => Some(source),
+            XrRecordingError::Serialize(source) => Some(source),
+            XrRecordingError::Parse(source) => Some(source),
+            XrRecordingError::Corrupt(_) | XrRecordingError::TooLarge(_) => None,
+        }
+    }
+}
+
+/// Frames captured by the [`XrRecorder`], replay them with [`XrRecording::into_script`] and the
+/// [`MockXrPlugin`](crate::prelude::MockXrPlugin)
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct XrRecording {
+    pub frames: Vec<XrMockFrame>,
+}
+
+impl XrRecording {
+    pub fn into_script(self) -> XrMockScript {
+        XrMockScript::new(self.frames)
+    }
+
+    pub fn to_ron(&self) -> Result<String, XrRecordingError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(XrRecordingError::Serialize)
+    }
+
+    pub fn from_ron(string: &str) -> Result<Self, XrRecordingError> {
+        ron::from_str(string).map_err(XrRecordingError::Parse)
+    }
+
+    /// Compact binary form, action names are stored once in a table at the start
+    pub fn to_bytes(&self) -> Result<Vec<u8>, XrRecordingError> {
+        let mut actions: Vec<&XrAction> = Vec::new();
+        for frame in self.frames.iter() {
+            let used = frame
+                .spaces
+                .iter()
+                .map(|space| &space.action)
+                .chain(frame.inputs.iter().map(|input| &input.action));
+            for action in used {
+                if !actions.contains(&action) {
+                    actions.push(action);
+                }
+            }
+        }
+        let index = |action: &XrAction| {
+            let i = actions
+                .iter()
+                .position(|known| *known == action)
+                .expect("every action was added to the table above");
+            len_u16(i, "actions")
+        };
+        let mut body = BinWriter::default();
+        body.u32(
+            u32::try_from(self.frames.len())
+                .map_err(|_| XrRecordingError::TooLarge(format!("{} frames", self.frames.len())))?,
+        );
+        for frame in self.frames.iter() {
+            body.pose(&frame.head);
+            body.vec3(frame.head_linear_velocity);
+            body.vec3(frame.head_angular_velocity);
+            body.u16(len_u16(frame.spaces.len(), "spaces in a frame")?);
+            for space in frame.spaces.iter() {
+                body.u16(index(&space.action)?);
+                body.hand(space.hand);
+                body.pose(&space.transform);
+                body.vec3(space.linear_velocity);
+                body.vec3(space.angular_velocity);
+            }
+            body.u16(len_u16(frame.inputs.len(), "inputs in a frame")?);
+            for input in frame.inputs.iter() {
+                body.u16(index(&input.action)?);
+                body.hand(input.hand);
+                match input.value {
+                    XrMockValue::Float(value) => {
+                        body.u8(0);
+                        body.f32(value);
+                    }
+                    XrMockValue::Bool(value) => {
+                        body.u8(1);
+                        body.u8(value as u8);
+                    }
+                    XrMockValue::Vec2(value) => {
+                        body.u8(2);
+                        body.f32(value.x);
+                        body.f32(value.y);
+                    }
+                }
+            }
+        }
+
+        let mut out = BinWriter::default();
+        out.0.extend_from_slice(MAGIC);
+        out.u8(VERSION);
+        out.u16(len_u16(actions.len(), "actions")?);
+        for action in actions {
+            out.string(&action.name)?;
+            out.string(&action.pretty_name)?;
+            out.u8(action_type_tag(&action.action_type));
+        }
+        out.0.extend_from_slice(&body.0);
+        Ok(out.0)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, XrRecordingError> {
+        let mut reader = BinReader { bytes, pos: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(XrRecordingError::Corrupt("not a recording".to_string()));
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(XrRecordingError::Corrupt(format!(
+                "unsupported version {version}"
+            )));
+        }
+        let mut actions = Vec::new();
+        for _ in 0..reader.u16()? {
+            actions.push(XrAction {
+                name: reader.string()?,
+                pretty_name: reader.string()?,
+                action_type: action_type_from_tag(reader.u8()?)?,
+            });
+        }
+        let action_at = |i: u16| {
+            actions
+                .get(i as usize)
+                .cloned()
+                .ok_or_else(|| XrRecordingError::Corrupt(format!("no action {i}")))
+        };
+
+        let mut frames = Vec::new();
+        for _ in 0..reader.u32()? {
+            let mut frame = XrMockFrame {
+                head: reader.pose()?,
+                head_linear_velocity: reader.vec3()?,
+                head_angular_velocity: reader.vec3()?,
+                ..default()
+            };
+            for _ in 0..reader.u16()? {
+                frame.spaces.push(XrMockSpace {
+                    action: action_at(reader.u16()?)?,
+                    hand: reader.hand()?,
+                    transform: reader.pose()?,
+                    linear_velocity: reader.vec3()?,
+                    angular_velocity: reader.vec3()?,
+                });
+            }
+            for _ in 0..reader.u16()? {
+                let action = action_at(reader.u16()?)?;
+                let hand = reader.hand()?;
+                let value = match reader.u8()? {
+                    0 => XrMockValue::Float(reader.f32()?),
+                    1 => XrMockValue::Bool(reader.u8()? != 0),
+                    2 => XrMockValue::Vec2(Vec2::new(reader.f32()?, reader.f32()?)),
+                    tag => return Err(XrRecordingError::Corrupt(format!("unknown value {tag}"))),
+                };
+                frame.inputs.push(XrMockInput {
+                    action,
+                    hand,
+                    value,
+                });
+            }
+            frames.push(frame);
+        }
+        Ok(Self { frames })
+    }
+
+    /// Writes RON if `path` ends in `.ron`, the binary format otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), XrRecordingError> {
+        let path = path.as_ref();
+        let bytes = if path.extension().is_some_and(|ext| ext == "ron") {
+            self.to_ron()?.into_bytes()
+        } else {
+            self.to_bytes()?
+        };
+        std::fs::write(path, bytes).map_err(|source| XrRecordingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Reads either format
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, XrRecordingError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|source| XrRecordingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if bytes.starts_with(MAGIC) {
+            Self::from_bytes(&bytes)
+        } else {
+            let string = String::from_utf8(bytes)
+                .map_err(|_| XrRecordingError::Corrupt("neither binary nor RON".to_string()))?;
+            Self::from_ron(&string)
+        }
+    }
+}
+
+fn action_type_tag(action_type: &XrActionType) -> u8 {
+    match action_type {
+        XrActionType::Float => 0,
+        XrActionType::Vec2 => 1,
+        XrActionType::Bool => 2,
+        XrActionType::Pose => 3,
+        XrActionType::Haptic => 4,
+    }
+}
+
+fn action_type_from_tag(tag: u8) -> Result<XrActionType, XrRecordingError> {
+    match tag {
+        0 => Ok(XrActionType::Float),
+        1 => Ok(XrActionType::Vec2),
+        2 => Ok(XrActionType::Bool),
+        3 => Ok(XrActionType::Pose),
+        4 => Ok(XrActionType::Haptic),
+        _ => Err(XrRecordingError::Corrupt(format!(
+            "unknown action type {tag}"
+        ))),
+    }
+}
+
+/// Lengths and indices are stored as u16
+fn len_u16(len: usize, what: &str) -> Result<u16, XrRecordingError> {
+    u16::try_from(len).map_err(|_| XrRecordingError::TooLarge(format!("{len} {what}")))
+}
+
+#[derive(Default)]
+struct BinWriter(Vec<u8>);
+
+impl BinWriter {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn vec3(&mut self, value: Vec3) {
+        self.f32(value.x);
+        self.f32(value.y);
+        self.f32(value.z);
+    }
+
+    /// Translation and rotation, poses from the runtime have no scale
+    fn pose(&mut self, transform: &Transform) {
+        self.vec3(transform.translation);
+        self.f32(transform.rotation.x);
+        self.f32(transform.rotation.y);
+        self.f32(transform.rotation.z);
+        self.f32(transform.rotation.w);
+    }
+
+    fn hand(&mut self, hand: Option<Hand>) {
+        self.u8(match hand {
+            None => 0,
+            Some(Hand::Left) => 1,
+            Some(Hand::Right) => 2,
+        });
+    }
+
+    fn string(&mut self, value: &str) -> Result<(), XrRecordingError> {
+        self.u16(len_u16(value.len(), "bytes in an action name")?);
+        self.0.extend_from_slice(value.as_bytes());
+        Ok(())
+    }
+}
+
+struct BinReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BinReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], XrRecordingError> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| XrRecordingError::Corrupt("unexpected end of file".to_string()))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], XrRecordingError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, XrRecordingError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, XrRecordingError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, XrRecordingError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, XrRecordingError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn vec3(&mut self) -> Result<Vec3, XrRecordingError> {
+        Ok(Vec3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+
+    fn pose(&mut self) -> Result<Transform, XrRecordingError> {
+        let translation = self.vec3()?;
+        let rotation = Quat::from_xyzw(self.f32()?, self.f32()?, self.f32()?, self.f32()?);
+        Ok(Transform::from_translation(translation).with_rotation(rotation))
+    }
+
+    fn hand(&mut self) -> Result<Option<Hand>, XrRecordingError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(Hand::Left)),
+            2 => Ok(Some(Hand::Right)),
+            tag => Err(XrRecordingError::Corrupt(format!("unknown hand {tag}"))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, XrRecordingError> {
+        let len = self.u16()? as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| XrRecordingError::Corrupt("action name isn't UTF-8".to_string()))
+    }
+}
+
+/// Captures a frame of input, head and pose spaces every update while `active` is set
+#[derive(Resource, Default)]
+pub struct XrRecorder {
+    pub active: bool,
+    pub recording: XrRecording,
+}
+
+impl XrRecorder {
+    pub fn start(&mut self) {
+        self.recording.frames.clear();
+        self.active = true;
+    }
+
+    /// Stops recording and hands back what was captured
+    pub fn stop(&mut self) -> XrRecording {
+        self.active = false;
+        std::mem::take(&mut self.recording)
+    }
+}
+
+pub struct XrRecorderPlugin;
+
+impl Plugin for XrRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrRecorder>();
+        app.add_systems(
+            PostUpdate,
+            record_frame
+                .before(end_frame_input)
+                .run_if(|recorder: Res<XrRecorder>| recorder.active),
+        );
+    }
+}
+
+/// `None` for poses, those are recorded from their spaces
+fn mock_value(state: &XrActionState) -> Option<XrMockValue> {
+    match state {
+        XrActionState::Float(state) => Some(XrMockValue::Float(state.cur_val)),
+        XrActionState::Bool(state) => Some(XrMockValue::Bool(state.cur_val)),
+        // replaying runs it through the deadzones again
+        XrActionState::Vec2(state) => Some(XrMockValue::Vec2(state.raw_val)),
+        XrActionState::Pose(_) => None,
+    }
+}
+
+pub fn record_frame(
+    mut recorder: ResMut<XrRecorder>,
+    inputs: Option<Res<XrInput>>,
+    head_query: Query<(&Transform, &XrVelocity), With<XrHead>>,
+    space_query: Query<(&Transform, &XrVelocity, &XrAction, Option<&Hand>), With<XrSpace>>,
+) {
+    let mut frame = XrMockFrame::default();
+    if let Ok((transform, velocity)) = head_query.get_single() {
+        frame.head = *transform;
+        frame.head_linear_velocity = velocity.linear;
+        frame.head_angular_velocity = velocity.angular;
+    }
+    for (transform, velocity, action, hand) in space_query.iter() {
+        frame.spaces.push(XrMockSpace {
+            action: action.clone(),
+            hand: hand.copied(),
+            transform: *transform,
+            linear_velocity: velocity.linear,
+            angular_velocity: velocity.angular,
+        });
+    }
+    if let Some(inputs) = inputs {
+        for (action, state) in inputs.state.iter() {
+            if let Some(value) = mock_value(state) {
+                frame.inputs.push(XrMockInput {
+                    action: action.clone(),
+                    hand: None,
+                    value,
+                });
+            }
+        }
+        for ((action, hand), state) in inputs.hands.iter() {
+            if let Some(value) = mock_value(state) {
+                frame.inputs.push(XrMockInput {
+                    action: action.clone(),
+                    hand: Some(*hand),
+                    value,
+                });
+            }
+        }
+    }
+    recorder.recording.frames.push(frame);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_rapier3d::prelude::Velocity;
+
+    use super::*;
+    use crate::{
+        mock::{mock_app, spawn_grabbable, spawn_hand},
+        prelude::{GravityGrabbing, Holding},
+    };
+
+    fn recording() -> XrRecording {
+        let action = |name: &str, action_type| XrAction {
+            name: name.to_string(),
+            pretty_name: name.to_uppercase(),
+            action_type,
+        };
+        let pose = action("pose", XrActionType::Pose);
+        let frame = |t: f32| XrMockFrame {
+            head: Transform::from_xyz(t, 1.6, -t).with_rotation(Quat::from_rotation_y(t)),
+            head_linear_velocity: Vec3::new(t, 0.0, 0.0),
+            head_angular_velocity: Vec3::new(0.0, t, 0.0),
+            spaces: vec![XrMockSpace {
+                action: pose.clone(),
+                hand: Some(Hand::Right),
+                transform: Transform::from_xyz(0.2, 1.0, -t),
+                linear_velocity: Vec3::Z,
+                angular_velocity: Vec3::X * t,
+            }],
+            inputs: vec![
+                XrMockInput {
+                    action: action("squeeze", XrActionType::Float),
+                    hand: Some(Hand::Left),
+                    value: XrMockValue::Float(t),
+                },
+                XrMockInput {
+                    action: action("menu", XrActionType::Bool),
+                    hand: None,
+                    value: XrMockValue::Bool(t > 0.5),
+                },
+                XrMockInput {
+                    action: action("joystick", XrActionType::Vec2),
+                    hand: Some(Hand::Right),
+                    value: XrMockValue::Vec2(Vec2::new(-t, t)),
+                },
+            ],
+        };
+        XrRecording {
+            frames: vec![frame(0.25), frame(0.75)],
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let recording = recording();
+        let bytes = recording.to_bytes().unwrap();
+        let decoded = XrRecording::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_ron().unwrap(), recording.to_ron().unwrap());
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let recording = recording();
+        let decoded = XrRecording::from_ron(&recording.to_ron().unwrap()).unwrap();
+        assert_eq!(decoded.to_ron().unwrap(), recording.to_ron().unwrap());
+    }
+
+    #[test]
+    fn truncated_binary_is_corrupt() {
+        let bytes = recording().to_bytes().unwrap();
+        assert!(matches!(
+            XrRecording::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(XrRecordingError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn oversized_name_is_rejected() {
+        let mut recording = recording();
+        recording.frames[0].inputs[0].action.name = "a".repeat(u16::MAX as usize + 1);
+        assert!(matches!(
+            recording.to_bytes(),
+            Err(XrRecordingError::TooLarge(_))
+        ));
+    }
+
+    /// Replays a recording from `tests/fixtures` with a tracked `hand` at the origin and a cube
+    fn replay(bytes: &[u8], hand: Hand, object: Vec3) -> (App, Entity, Entity) {
+        let recording = XrRecording::from_bytes(bytes).unwrap();
+        let mut app = mock_app(recording.into_script());
+        let hand = spawn_hand(&mut app, hand);
+        let object = spawn_grabbable(&mut app, object);
+        (app, hand, object)
+    }
+
+    /// Recorded with the [`XrRecorderPlugin`], the left hand points down and forward at a cube a
+    /// meter below and in front of it, squeezes on frames 2 and 3 and flicks on frame 4
+    #[test]
+    fn replayed_gravity_flick() {
+        let (mut app, hand, object) = replay(
+            include_bytes!("../tests/fixtures/gravity_flick.xrrec"),
+            Hand::Left,
+            Vec3::new(0.0, -1.0, -1.0),
+        );
+        for _ in 0..2 {
+            app.update();
+        }
+        assert!(app.world().get::<GravityGrabbing>(object).is_none());
+        for _ in 0..2 {
+            app.update();
+            let grabbing = app.world().get::<GravityGrabbing>(object);
+            assert_eq!(grabbing.map(|grabbing| grabbing.0), Some(hand));
+        }
+        app.update();
+        assert!(app.world().get::<GravityGrabbing>(object).is_none());
+        // launched at 60 degrees so it lands a meter back and a meter up, in the hand
+        let velocity = app.world().get::<Velocity>(object).unwrap().linvel;
+        assert!(
+            velocity.abs_diff_eq(Vec3::new(0.0, 4.4834, 2.5885), 1e-3),
+            "{velocity}"
+        );
+    }
+
+    /// Recorded with the [`XrRecorderPlugin`], the right hand squeezes on frames 2 and 3 and lets
+    /// go on frame 4 while moving up and to the right
+    #[test]
+    fn replayed_throw() {
+        let (mut app, hand, object) = replay(
+            include_bytes!("../tests/fixtures/grab_throw.xrrec"),
+            Hand::Right,
+            Vec3::ZERO,
+        );
+        for _ in 0..2 {
+            app.update();
+        }
+        assert!(app.world().get::<Holding>(object).is_none());
+        for _ in 0..2 {
+            app.update();
+            let holding = app.world().get::<Holding>(object);
+            assert_eq!(holding.map(|holding| holding.0), Some(hand));
+        }
+        app.update();
+        assert!(app.world().get::<Holding>(object).is_none());
+        let velocity = app.world().get::<Velocity>(object).unwrap().linvel;
+        assert!(
+            velocity.abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1e-4),
+            "{velocity}"
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use bevy::{input::mouse::AccumulatedMouseMotion, prelude::*, utils::HashMap};
+use bevy_mod_openxr::resources::OxrViews;
+use bevy_mod_xr::{
+    session::{session_available, XrTrackingRoot},
+    spaces::XrVelocity,
+};
+
+use crate::{
+    input::{
+        actions::{
+            update_pose_world, update_spaces, Hand, XrAction, XrActionState, XrHead, XrInput,
+            XrSpace,
+        },
+        config::{Config, DefaultActions},
+        typed::XrTypedAction,
+    },
+    mock::{apply_frame, XrMockFrame, XrMockInput, XrMockSpace, XrMockValue},
+};
+
+/// Keys and buttons driving the simulator
+#[derive(Clone, Debug)]
+pub struct XrSimulatorKeys {
+    /// Held to turn the head with the mouse
+    pub look: MouseButton,
+    /// Held to move and turn the left hand instead of the head
+    pub left_hand: KeyCode,
+    /// Held to move and turn the right hand instead of the head
+    pub right_hand: KeyCode,
+    pub forward: KeyCode,
+    pub back: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub up: KeyCode,
+    pub down: KeyCode,
+    /// Squeezes the controlled hand, the right one while controlling the head
+    pub squeeze: MouseButton,
+    /// Pushes the joystick of the controlled hand, the left one while controlling the head
+    pub joystick_up: KeyCode,
+    pub joystick_down: KeyCode,
+    pub joystick_left: KeyCode,
+    pub joystick_right: KeyCode,
+}
+
+impl Default for XrSimulatorKeys {
+    fn default() -> Self {
+        Self {
+            look: MouseButton::Right,
+            left_hand: KeyCode::ShiftLeft,
+            right_hand: KeyCode::ControlLeft,
+            forward: KeyCode::KeyW,
+            back: KeyCode::KeyS,
+            left: KeyCode::KeyA,
+            right: KeyCode::KeyD,
+            up: KeyCode::KeyE,
+            down: KeyCode::KeyQ,
+            squeeze: MouseButton::Left,
+            joystick_up: KeyCode::ArrowUp,
+            joystick_down: KeyCode::ArrowDown,
+            joystick_left: KeyCode::ArrowLeft,
+            joystick_right: KeyCode::ArrowRight,
+        }
+    }
+}
+
+/// State of the virtual head and hands, hands are relative to the head so they follow it
+#[derive(Resource, Clone, Debug)]
+pub struct XrSimulator {
+    pub keys: XrSimulatorKeys,
+    pub head: Transform,
+    pub hands: HashMap<Hand, Transform>,
+    /// Meters per second
+    pub move_speed: f32,
+    /// Radians per pixel
+    pub look_sensitivity: f32,
+    pub squeeze_action: XrAction,
+    pub joystick_action: XrAction,
+    pub pose_action: XrAction,
+    previous: HashMap<Option<Hand>, Transform>,
+}
+
+impl Default for XrSimulator {
+    fn default() -> Self {
+        Self {
+            keys: XrSimulatorKeys::default(),
+            head: Transform::from_xyz(0.0, 1.7, 0.0),
+            hands: HashMap::from_iter([
+                (Hand::Left, Transform::from_xyz(-0.2, -0.4, -0.4)),
+                (Hand::Right, Transform::from_xyz(0.2, -0.4, -0.4)),
+            ]),
+            move_speed: 1.5,
+            look_sensitivity: 0.003,
+            squeeze_action: DefaultActions::Squeeze.action(),
+            joystick_action: DefaultActions::Joystick.action(),
+            pose_action: DefaultActions::Pose.action(),
+            previous: HashMap::new(),
+        }
+    }
+}
+
+impl XrSimulator {
+    /// Where `hand` is in the tracking root
+    pub fn hand_transform(&self, hand: Hand) -> Transform {
+        self.head * self.hands.get(&hand).copied().unwrap_or_default()
+    }
+
+    /// Velocities of `transform` since it was last seen under `key`
+    fn velocity(&mut self, key: Option<Hand>, transform: Transform, delta: f32) -> (Vec3, Vec3) {
+        let previous = self.previous.insert(key, transform).unwrap_or(transform);
+        if delta <= 0.0 {
+            return (Vec3::ZERO, Vec3::ZERO);
+        }
+        let linear = (transform.translation - previous.translation) / delta;
+        let angular = (transform.rotation * previous.rotation.inverse()).to_scaled_axis() / delta;
+        (linear, angular)
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub struct XrSimulatorSet;
+
+/// Fakes a headset and two controllers with the mouse and keyboard while no XR session is
+/// available. Writes the same [`XrInput`], [`XrHead`], pose spaces and [`XrVelocity`] the
+/// OpenXR backend does, see [`XrSimulatorKeys`] for the controls.
+pub struct XrSimulatorPlugin;
+
+impl Plugin for XrSimulatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrSimulator>();
+        app.init_resource::<OxrViews>();
+        app.configure_sets(
+            PreUpdate,
+            XrSimulatorSet
+                .before(update_spaces)
+                .before(update_pose_world)
+                .run_if(not(session_available)),
+        );
+        app.add_systems(
+            PreUpdate,
+            (setup_simulator, simulate).chain().in_set(XrSimulatorSet),
+        );
+    }
+}
+
+fn setup_simulator(
+    config: Res<Config>,
+    inputs: Option<Res<XrInput>>,
+    root_query: Query<(), With<XrTrackingRoot>>,
+    head_query: Query<(), With<XrHead>>,
+    mut cmds: Commands,
+) {
+    if inputs.is_none() {
+        cmds.insert_resource(XrInput::from_config(&config));
+    }
+    if root_query.is_empty() {
+        cmds.spawn((Transform::default(), Visibility::default(), XrTrackingRoot));
+    }
+    if head_query.is_empty() {
+        cmds.spawn((
+            Transform::default(),
+            Visibility::default(),
+            XrVelocity::new(),
+            XrHead,
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn simulate(
+    mut simulator: ResMut<XrSimulator>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mouse: Res<AccumulatedMouseMotion>,
+    time: Res<Time>,
+    inputs: Option<ResMut<XrInput>>,
+    mut views: ResMut<OxrViews>,
+    mut head_query: Query<(&mut Transform, &mut XrVelocity), (With<XrHead>, Without<XrSpace>)>,
+    mut space_query: Query<
+        (&mut Transform, &mut XrVelocity, &XrAction, Option<&Hand>),
+        (With<XrSpace>, Without<XrHead>),
+    >,
+    mut cmds: Commands,
+) {
+    // inserted by setup_simulator, shows up next frame
+    let Some(mut inputs) = inputs else {
+        return;
+    };
+    let simulator = &mut *simulator;
+    let delta = time.delta_secs();
+    let controlled = if keys.pressed(simulator.keys.left_hand) {
+        Some(Hand::Left)
+    } else if keys.pressed(simulator.keys.right_hand) {
+        Some(Hand::Right)
+    } else {
+        None
+    };
+
+    let axis = |positive: KeyCode, negative: KeyCode| {
+        keys.pressed(positive) as i32 as f32 - keys.pressed(negative) as i32 as f32
+    };
+    let movement = Vec3::new(
+        axis(simulator.keys.right, simulator.keys.left),
+        axis(simulator.keys.up, simulator.keys.down),
+        axis(simulator.keys.back, simulator.keys.forward),
+    ) * simulator.move_speed
+        * delta;
+    let look = if buttons.pressed(simulator.keys.look) {
+        mouse.delta * simulator.look_sensitivity
+    } else {
+        Vec2::ZERO
+    };
+
+    match controlled {
+        Some(hand) => {
+            let transform = simulator.hands.entry(hand).or_default();
+            transform.translation += movement;
+            transform.rotate_local_y(-look.x);
+            transform.rotate_local_x(-look.y);
+        }
+        None => {
+            // walk along the ground like a first person camera
+            let (yaw, pitch, _) = simulator.head.rotation.to_euler(EulerRot::YXZ);
+            let yaw = yaw - look.x;
+            let pitch = (pitch - look.y).clamp(-1.5, 1.5);
+            simulator.head.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+            simulator.head.translation += Quat::from_rotation_y(yaw) * movement;
+        }
+    }
+
+    let squeezing = controlled.unwrap_or(Hand::Right);
+    let steering = controlled.unwrap_or(Hand::Left);
+    let joystick = Vec2::new(
+        axis(simulator.keys.joystick_right, simulator.keys.joystick_left),
+        axis(simulator.keys.joystick_up, simulator.keys.joystick_down),
+    );
+    let squeeze = if buttons.pressed(simulator.keys.squeeze) {
+        1.0
+    } else {
+        0.0
+    };
+
+    let head = simulator.head;
+    let (head_linear_velocity, head_angular_velocity) = simulator.velocity(None, head, delta);
+    let mut frame = XrMockFrame {
+        head,
+        head_linear_velocity,
+        head_angular_velocity,
+        ..default()
+    };
+    for hand in Hand::ALL {
+        let transform = simulator.hand_transform(hand);
+        let (linear_velocity, angular_velocity) = simulator.velocity(Some(hand), transform, delta);
+        frame.spaces.push(XrMockSpace {
+            action: simulator.pose_action.clone(),
+            hand: Some(hand),
+            transform,
+            linear_velocity,
+            angular_velocity,
+        });
+        frame.inputs.push(XrMockInput {
+            action: simulator.squeeze_action.clone(),
+            hand: Some(hand),
+            value: XrMockValue::Float(if hand == squeezing { squeeze } else { 0.0 }),
+        });
+        frame.inputs.push(XrMockInput {
+            action: simulator.joystick_action.clone(),
+            hand: Some(hand),
+            value: XrMockValue::Vec2(if hand == steering {
+                joystick
+            } else {
+                Vec2::ZERO
+            }),
+        });
+    }
+    frame.inputs.push(XrMockInput {
+        action: simulator.squeeze_action.clone(),
+        hand: None,
+        value: XrMockValue::Float(squeeze),
+    });
+    frame.inputs.push(XrMockInput {
+        action: simulator.joystick_action.clone(),
+        hand: None,
+        value: XrMockValue::Vec2(joystick),
+    });
+    // a custom config might not have the default actions
+    frame
+        .inputs
+        .retain(|input| inputs.get(&input.action).is_some());
+
+    apply_frame(
+        &frame,
+        &mut inputs,
+        &mut views,
+        &mut head_query,
+        &mut space_query,
+        &mut cmds,
+    );
+
+    // like a runtime the combined pose is one of the hands', the one the combined squeeze is from
+    let pose = inputs
+        .get_hand(&simulator.pose_action, squeezing)
+        .and_then(XrActionState::as_pose)
+        .copied();
+    if let (Some(pose), Some(XrActionState::Pose(state))) =
+        (pose, inputs.get_mut(&simulator.pose_action, None))
+    {
+        *state = pose;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{input::InputPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::mock::test_app;
+
+    fn app() -> App {
+        let mut app = test_app();
+        app.add_plugins((InputPlugin, XrSimulatorPlugin));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        app
+    }
+
+    fn hold(app: &mut App, keys: &[KeyCode], frames: usize) {
+        for _ in 0..frames {
+            let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            input.release_all();
+            for key in keys {
+                input.press(*key);
+            }
+            app.update();
+        }
+    }
+
+    fn pose(app: &App, hand: Option<Hand>) -> Transform {
+        let inputs = app.world().resource::<XrInput>();
+        let pose = DefaultActions::Pose.action();
+        let state = match hand {
+            Some(hand) => inputs.get_hand(&pose, hand),
+            None => inputs.get(&pose),
+        };
+        state.unwrap().as_pose().unwrap().local
+    }
+
+    #[test]
+    fn keys_move_the_controlled_hand() {
+        let mut app = app();
+        hold(&mut app, &[], 1);
+        let start = pose(&app, Some(Hand::Right));
+        let left = pose(&app, Some(Hand::Left));
+
+        hold(&mut app, &[KeyCode::ControlLeft, KeyCode::KeyW], 3);
+        let moved = pose(&app, Some(Hand::Right));
+        assert!((start.translation.z - moved.translation.z - 0.45).abs() < 1e-4);
+        assert_eq!(pose(&app, Some(Hand::Left)), left);
+    }
+
+    #[test]
+    fn combined_pose_follows_the_squeezing_hand() {
+        let mut app = app();
+        hold(&mut app, &[], 1);
+        // the right hand squeezes while the head is controlled
+        assert_eq!(pose(&app, None), pose(&app, Some(Hand::Right)));
+
+        hold(&mut app, &[KeyCode::ShiftLeft, KeyCode::KeyA], 2);
+        assert_eq!(pose(&app, None), pose(&app, Some(Hand::Left)));
+    }
+}